@@ -1,11 +1,15 @@
 use anyhow::Result;
+use num::complex::Complex;
 use num::Float;
 
 use std::cmp;
 use std::convert::From;
 use std::ops::{Add, AddAssign, Div};
 
+use crate::{fft, stats};
+
 /// Calculate the auto-correlation function of a time series of length n.
+/// For long series with many lags, this uses the fast Fourier transform, see `acf_fft`.
 ///
 /// # Arguments
 ///
@@ -21,7 +25,7 @@ use std::ops::{Add, AddAssign, Div};
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let ac = acf::acf(&x, Some(2), false).unwrap();
 /// assert!((ac[0] - 1.0).abs() < 1.0e-7);
 /// assert!((ac[1] - 0.25).abs() < 1.0e-7);
@@ -37,6 +41,24 @@ pub fn acf<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
         Some(max_lag) => cmp::min(max_lag, x.len() - 1),
         None => x.len() - 1,
     };
+
+    // the direct method needs n*(max_lag+1) operations, the FFT about 2*N*log2(N) for
+    // the padded length N
+    let n_fft = (x.len() + max_lag + 1).next_power_of_two();
+    let cost_fft = 2.0 * n_fft as f64 * (n_fft as f64).log2();
+    if (x.len() * (max_lag + 1)) as f64 > cost_fft {
+        acf_fft(x, Some(max_lag), covariance)
+    } else {
+        acf_direct(x, max_lag, covariance)
+    }
+}
+
+/// Auto-covariances or auto-correlations by the direct sum over all pairs of observations.
+fn acf_direct<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
+    x: &[T],
+    max_lag: usize,
+    covariance: bool,
+) -> Result<Vec<T>> {
     let m = max_lag + 1;
 
     let len_x_usize = x.len();
@@ -66,6 +88,241 @@ pub fn acf<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
     Ok(y)
 }
 
+/// Calculate the auto-correlation function of a time series of length n with the fast
+/// Fourier transform of the zero-padded series. This takes O(n log n) operations instead
+/// of the O(n max_lag) of the direct sums and gives the same results up to rounding.
+/// `acf` chooses it automatically for long series with many lags.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `max_lag` - Calculate ACF for this maximum lag. Defaults to n-1.
+/// * `covariance` - If true, returns auto-covariances. If false, returns auto-correlations.
+///
+/// # Returns
+///
+/// * Output vector of length max_lag+1.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
+/// let ac = acf::acf_fft(&x, Some(2), false).unwrap();
+/// assert!((ac[1] - 0.25).abs() < 1.0e-7);
+/// assert!((ac[2] - (-0.3)).abs() < 1.0e-7);
+/// ```
+pub fn acf_fft<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
+    x: &[T],
+    max_lag: Option<usize>,
+    covariance: bool,
+) -> Result<Vec<T>> {
+    let max_lag = match max_lag {
+        // if upper bound for max_lag is n-1
+        Some(max_lag) => cmp::min(max_lag, x.len() - 1),
+        None => x.len() - 1,
+    };
+    let n = x.len();
+
+    // pad with zeros to avoid circular overlap of the lags
+    let n_fft = (n + max_lag + 1).next_power_of_two();
+    let x64: Vec<f64> = x.iter().map(|xi| xi.to_f64().unwrap_or(f64::NAN)).collect();
+    let mean = x64.iter().sum::<f64>() / n as f64;
+    let mut buf = vec![Complex::new(0.0, 0.0); n_fft];
+    for (b, xi) in buf.iter_mut().zip(&x64) {
+        b.re = xi - mean;
+    }
+
+    // the inverse transform of the periodogram is the circular autocovariance
+    fft::fft(&mut buf, false);
+    for b in buf.iter_mut() {
+        *b = Complex::new(b.norm_sqr(), 0.0);
+    }
+    fft::fft(&mut buf, true);
+
+    let scale = (n_fft * n) as f64;
+    let gamma0 = buf[0].re / scale;
+    let y = buf[..=max_lag]
+        .iter()
+        .enumerate()
+        .map(|(t, b)| match (covariance, t) {
+            (true, _) => From::from(b.re / scale),
+            (false, 0) => From::from(1.0),
+            (false, _) => From::from(b.re / scale / gamma0),
+        })
+        .collect();
+    Ok(y)
+}
+
+/// Calculate the cross-correlation function of two time series of length n. The value at
+/// lag k estimates the correlation of `x[t+k]` and `y[t]`, like R's `ccf`, so that a peak at
+/// a negative lag means that `x` leads `y`. The normalization is the same as in `acf`:
+/// the sums are divided by n, and the correlations by the square root of the product of
+/// the variances.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to first input vector slice of length n.
+/// * `&y` - Reference to second input vector slice of length n.
+/// * `max_lag` - Calculate CCF for the lags -max_lag, ..., max_lag. Defaults to n-1.
+/// * `covariance` - If true, returns cross-covariances. If false, returns cross-correlations.
+/// * `prewhiten` - If set, both series are first filtered with an AR model of this order
+///   fitted to `x` with `ar`, which removes spurious correlations caused by the
+///   auto-correlation of `x`. The filtered series are shorter by the order.
+///
+/// # Returns
+///
+/// * Output vector of length 2*max_lag+1 with the lags -max_lag, ..., max_lag, i.e. lag
+///   zero is at index max_lag.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// let x: [f64; 8] = [2.0, 5.0, 1.0, 4.0, 0.0, 3.0, 6.0, 1.0];
+/// // y follows x with a delay of one step
+/// let y: [f64; 8] = [3.0, 2.0, 5.0, 1.0, 4.0, 0.0, 3.0, 6.0];
+/// let cc = acf::ccf(&x, &y, Some(2), false, None).unwrap();
+/// assert_eq!(cc.len(), 5);
+/// // the largest correlation is at lag -1
+/// assert!(cc.iter().all(|&c| c <= cc[1]));
+/// ```
+pub fn ccf<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAssign>(
+    x: &[T],
+    y: &[T],
+    max_lag: Option<usize>,
+    covariance: bool,
+    prewhiten: Option<usize>,
+) -> Result<Vec<T>> {
+    if x.len() != y.len() {
+        anyhow::bail!("Time series must have the same length");
+    }
+
+    // filter both series with the AR model of x
+    let (x, y) = match prewhiten {
+        Some(order) => {
+            if x.len() <= order + 1 {
+                anyhow::bail!("Time series is too short to prewhiten");
+            }
+            let (phi, _var) = ar(x, Some(order))?;
+            let filter = |z: &[T]| -> Vec<T> {
+                (phi.len()..z.len())
+                    .map(|t| {
+                        let mut e = z[t];
+                        for (i, p) in phi.iter().enumerate() {
+                            e = e - *p * z[t - i - 1];
+                        }
+                        e
+                    })
+                    .collect()
+            };
+            (filter(x), filter(y))
+        }
+        None => (x.to_vec(), y.to_vec()),
+    };
+    if x.is_empty() {
+        anyhow::bail!("Time series must not be empty");
+    }
+
+    let max_lag = match max_lag {
+        // if upper bound for max_lag is n-1
+        Some(max_lag) => cmp::min(max_lag, x.len() - 1),
+        None => x.len() - 1,
+    };
+
+    let len_z: T = From::from(x.len() as u32);
+    let zero: T = From::from(0.0);
+    let mean_x = x.iter().fold(zero, |sum, &xi| sum + xi) / len_z;
+    let mean_y = y.iter().fold(zero, |sum, &yi| sum + yi) / len_z;
+
+    // sum of (x[t+k] - mean_x) * (y[t] - mean_y) over all t
+    let cross = |a: &[T], mean_a: T, b: &[T], mean_b: T, k: usize| -> T {
+        let mut c = zero;
+        for t in 0..a.len() - k {
+            c += (a[t + k] - mean_a) * (b[t] - mean_b);
+        }
+        c / len_z
+    };
+
+    let mut c: Vec<T> = Vec::with_capacity(2 * max_lag + 1);
+    for k in (1..=max_lag).rev() {
+        c.push(cross(&y, mean_y, &x, mean_x, k));
+    }
+    for k in 0..=max_lag {
+        c.push(cross(&x, mean_x, &y, mean_y, k));
+    }
+
+    if !covariance {
+        let scale = (cross(&x, mean_x, &x, mean_x, 0) * cross(&y, mean_y, &y, mean_y, 0)).sqrt();
+        for ci in c.iter_mut() {
+            *ci = *ci / scale;
+        }
+    }
+    Ok(c)
+}
+
+/// Calculate the auto-correlation function of a time series of length n together with
+/// confidence intervals. The variances of the auto-correlations follow Bartlett's formula
+/// `(1 + 2 * sum_j<k rho_j^2) / n`, i.e. the interval at lag k assumes an MA(k-1) process,
+/// like statsmodels' `acf` with `alpha`.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `max_lag` - Calculate ACF for this maximum lag. Defaults to n-1.
+/// * `alpha` - Significance level in (0, 1), e.g. 0.05 for 95% intervals.
+///
+/// # Returns
+///
+/// * Tuple of the auto-correlations of length max_lag+1 and the (lower, upper) bounds of
+///   their confidence intervals. The interval at lag zero has zero width.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
+/// let (ac, confint) = acf::acf_with_confint(&x, Some(2), 0.05).unwrap();
+/// assert!((ac[1] - 0.25).abs() < 1.0e-7);
+/// // 1.96/sqrt(4) around the first auto-correlation
+/// assert!((confint[1].0 - (0.25 - 0.979982)).abs() < 1.0e-6);
+/// assert!((confint[1].1 - (0.25 + 0.979982)).abs() < 1.0e-6);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn acf_with_confint<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAssign>(
+    x: &[T],
+    max_lag: Option<usize>,
+    alpha: f64,
+) -> Result<(Vec<T>, Vec<(T, T)>)> {
+    let ppf = confint_quantile(alpha)?;
+    let rho = acf(x, max_lag, false)?;
+    let n = x.len() as f64;
+
+    // cumulative sum of the squared auto-correlations of the lower lags
+    let mut sum = 0.0;
+    let mut confint = Vec::with_capacity(rho.len());
+    for (k, r) in rho.iter().enumerate() {
+        let var = match k {
+            0 => 0.0,
+            _ => (1.0 + 2.0 * sum) / n,
+        };
+        if k > 0 {
+            sum += (*r).into().powi(2);
+        }
+        let h: T = From::from(ppf * var.sqrt());
+        confint.push((*r - h, *r + h));
+    }
+    Ok((rho, confint))
+}
+
+/// Quantile of the standard normal distribution for two-sided intervals at level alpha.
+fn confint_quantile(alpha: f64) -> Result<f64> {
+    if !(alpha > 0.0 && alpha < 1.0) {
+        anyhow::bail!("Significance level must be in (0, 1)");
+    }
+    Ok(stats::normal_ppf(1.0 - alpha / 2.0))
+}
+
 /// Calculate the auto-regressive coefficients of a time series of length n.
 /// If you already calculated the auto-correlation coefficients (ACF), consider
 /// using `ar_rho` instead.
@@ -83,7 +340,7 @@ pub fn acf<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let (ar, _var) = acf::ar(&x, Some(2)).unwrap();
 /// assert!((ar[0] - 0.3466667).abs() < 1.0e-7);
 /// assert!((ar[1] - -0.3866667).abs() < 1.0e-7);
@@ -115,7 +372,7 @@ pub fn ar<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAssign>(
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let rho = acf::acf(&x, None, false).unwrap();
 /// let ar = acf::ar_lapack_rho(&rho, Some(2)).unwrap();
 /// assert!((ar[0] - 0.3466667).abs() < 1.0e-7);
@@ -190,7 +447,7 @@ pub fn ar_lapack_rho<T: Float + From<f64> + Into<f64> + Copy>(
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let rho = acf::acf(&x, None, false).unwrap();
 /// let cov0 = acf::acf(&x, Some(0), true).unwrap()[0];
 /// let (ar, err) = acf::ar_dl_rho_cov(&rho, cov0, Some(2)).unwrap();
@@ -269,7 +526,7 @@ pub fn ar_dl_rho_cov<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign +
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// acf::var(&x, Some(2));
 /// ```
 pub fn var<T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign + Div>(
@@ -300,7 +557,7 @@ pub fn var<T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let rho = acf::acf(&x, Some(3), false).unwrap();
 /// let cov0 = acf::acf(&x, Some(0), true).unwrap()[0].clone();
 /// let (phi, _var) = acf::ar_dl_rho_cov(&rho, cov0, Some(2)).unwrap();
@@ -338,7 +595,7 @@ pub fn var_phi_rho_cov<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let pr = acf::pacf(&x, Some(2)).unwrap();
 /// assert!((pr[0] - 0.25).abs() < 1.0e-7);
 /// assert!((pr[1] - -0.3866667).abs() < 1.0e-7);
@@ -369,7 +626,7 @@ pub fn pacf<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAssign>(
 ///
 /// ```
 /// use arima::acf;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let rho = acf::acf(&x, None, false).unwrap();
 /// let cov0 = acf::acf(&x, Some(0), true).unwrap()[0];
 /// let pr = acf::pacf_rho_cov0(&rho, cov0, Some(2)).unwrap();
@@ -399,3 +656,207 @@ pub fn pacf_rho_cov0<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAs
     }
     Ok(y)
 }
+
+/// Calculate the partial auto-correlation coefficients of a time series of length n
+/// together with confidence intervals. The intervals are `pacf +- z / sqrt(n)` with the
+/// standard normal quantile z, i.e. they assume white noise.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `max_lag` - Maximum lag to calculate the PACF for. Defaults to n.
+/// * `alpha` - Significance level in (0, 1), e.g. 0.05 for 95% intervals.
+///
+/// # Returns
+///
+/// * Tuple of the partial auto-correlations of length `max_lag` and the (lower, upper)
+///   bounds of their confidence intervals.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
+/// let (pr, confint) = acf::pacf_with_confint(&x, Some(2), 0.05).unwrap();
+/// assert!((pr[0] - 0.25).abs() < 1.0e-7);
+/// assert!((confint[0].1 - (0.25 + 0.979982)).abs() < 1.0e-6);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn pacf_with_confint<T: Float + From<u32> + From<f64> + Into<f64> + Copy + AddAssign>(
+    x: &[T],
+    max_lag: Option<usize>,
+    alpha: f64,
+) -> Result<(Vec<T>, Vec<(T, T)>)> {
+    let ppf = confint_quantile(alpha)?;
+    let pacf = pacf(x, max_lag)?;
+    let h: T = From::from(ppf / (x.len() as f64).sqrt());
+    let confint = pacf.iter().map(|p| (*p - h, *p + h)).collect();
+    Ok((pacf, confint))
+}
+
+/// Run the innovations algorithm (Brockwell & Davis, Proposition 5.2.2) on the
+/// auto-covariances `cov` of a zero-mean process. The coefficients `theta_m,j` give the
+/// best linear one-step predictor `x^_m+1 = sum_j=1..m theta_m,j (x_m+1-j - x^_m+1-j)`
+/// from the previous innovations, and `v_m` is its mean squared error.
+///
+/// # Arguments
+///
+/// * `&cov` - Reference to auto-covariances for the lags 0, ..., at least `n`.
+/// * `n` - Number of steps. Defaults to the number of lags in `cov`.
+///
+/// # Returns
+///
+/// * Tuple of the coefficients `theta_m,1, ..., theta_m,m` for m = 1, ..., n, and the mean
+///   squared errors `v_0, ..., v_n`.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// // MA(1) process with theta = 0.5 and unit innovation variance
+/// let cov: [f64; 11] = [1.25, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+/// let (theta, v) = acf::innovations_cov(&cov, None).unwrap();
+/// assert!((theta[0][0] - 0.4).abs() < 1.0e-12);
+/// // the coefficients and errors converge to the MA coefficient and the innovation variance
+/// assert!((theta[9][0] - 0.5).abs() < 1.0e-3);
+/// assert!((v[10] - 1.0).abs() < 1.0e-3);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn innovations_cov<T: Float + From<u32> + From<f64> + Copy + AddAssign>(
+    cov: &[T],
+    n: Option<usize>,
+) -> Result<(Vec<Vec<T>>, Vec<T>)> {
+    if cov.is_empty() {
+        anyhow::bail!("Auto-covariances must not be empty");
+    }
+    let n = n.unwrap_or(cov.len() - 1);
+    if n >= cov.len() {
+        anyhow::bail!("Expected auto-covariances for at least {} lags", n);
+    }
+    let zero: T = From::from(0.0);
+
+    // theta[m-1][j-1] holds theta_m,j
+    let mut theta: Vec<Vec<T>> = Vec::with_capacity(n);
+    let mut v: Vec<T> = Vec::with_capacity(n + 1);
+    v.push(cov[0]);
+    for m in 1..=n {
+        if v[m - 1] <= zero {
+            anyhow::bail!("Auto-covariances are not positive definite");
+        }
+        let mut row = vec![zero; m];
+        for k in 0..m {
+            // theta_m,m-k = (cov(m-k) - sum_j<k theta_k,k-j theta_m,m-j v_j) / v_k
+            let mut sum = cov[m - k];
+            for j in 0..k {
+                sum = sum - theta[k - 1][k - j - 1] * row[m - j - 1] * v[j];
+            }
+            row[m - k - 1] = sum / v[k];
+        }
+        let mut vm = cov[0];
+        for j in 0..m {
+            vm = vm - row[m - j - 1] * row[m - j - 1] * v[j];
+        }
+        theta.push(row);
+        v.push(vm);
+    }
+    Ok((theta, v))
+}
+
+/// Estimate the coefficients of an MA model of a time series of length n with the
+/// innovations algorithm (Brockwell & Davis, Section 8.3). The estimates are the first
+/// `order` coefficients `theta_m,1, ..., theta_m,order` after m steps of the algorithm on the
+/// sample auto-covariances, with the mean squared error `v_m` as the variance estimate.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `order` - Order of the MA model.
+/// * `m` - Number of steps of the innovations algorithm, at least `order`. Defaults to
+///   `max(order, floor(10 log10(n)))`, capped at n-1.
+///
+/// # Returns
+///
+/// * Tuple of an output vector of length order containing the MA coefficients and an
+///   estimate for the variance.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// let x: [f64; 8] = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1];
+/// let (theta, var) = acf::ma(&x, 1, Some(4)).unwrap();
+/// assert_eq!(theta.len(), 1);
+/// assert!(var > 0.0);
+/// ```
+pub fn ma<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div>(
+    x: &[T],
+    order: usize,
+    m: Option<usize>,
+) -> Result<(Vec<T>, T)> {
+    if x.len() <= order {
+        anyhow::bail!("Time series is too short for an MA({}) model", order);
+    }
+    let m = m
+        .unwrap_or_else(|| ((10.0 * (x.len() as f64).log10()).floor() as usize).max(order))
+        .min(x.len() - 1);
+    if m < order {
+        anyhow::bail!("Number of steps must be at least the order");
+    }
+    let cov = acf(x, Some(m), true)?;
+    let (theta, v) = innovations_cov(&cov, Some(m))?;
+    let coef = match m {
+        0 => Vec::new(),
+        _ => theta[m - 1][..order].to_vec(),
+    };
+    Ok((coef, v[m]))
+}
+
+/// Calculate the exact one-step predictors of a zero-mean time series of length n and
+/// their mean squared errors with the innovations algorithm, given the auto-covariances
+/// of the process, e.g. from `arma::theoretical_acf`. The Gaussian log-likelihood of the
+/// series is `-0.5 * sum_t (ln(2 pi v_t-1) + (x_t - x^_t)^2 / v_t-1)`. This needs O(n^2)
+/// memory and O(n^3) operations, see `kalman::arma_loglik` for long ARMA series.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n. Subtract the mean first.
+/// * `&cov` - Reference to the auto-covariances of the process for the lags 0, ..., at
+///   least n.
+///
+/// # Returns
+///
+/// * Tuple of the predictors `x^_1, ..., x^_n+1` and their mean squared errors
+///   `v_0, ..., v_n`, both of length n+1. The last values are the forecast of the next
+///   observation.
+///
+/// # Example
+///
+/// ```
+/// use arima::acf;
+/// // AR(1) process with phi = 0.5 and unit innovation variance
+/// let cov: Vec<f64> = (0..4).map(|k| 0.5_f64.powi(k) / 0.75).collect();
+/// let (pred, v) = acf::innovations_predict(&[1.0, -2.0, 0.5], &cov).unwrap();
+/// // after the first observation, the predictor is phi * x_t with error variance 1
+/// assert!((pred[1] - 0.5).abs() < 1.0e-12 && (pred[2] + 1.0).abs() < 1.0e-12);
+/// assert!((pred[3] - 0.25).abs() < 1.0e-12);
+/// assert!((v[0] - 1.0 / 0.75).abs() < 1.0e-12 && (v[3] - 1.0).abs() < 1.0e-12);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn innovations_predict<T: Float + From<u32> + From<f64> + Copy + AddAssign>(
+    x: &[T],
+    cov: &[T],
+) -> Result<(Vec<T>, Vec<T>)> {
+    let n = x.len();
+    let (theta, v) = innovations_cov(cov, Some(n))?;
+    let zero: T = From::from(0.0);
+    let mut pred: Vec<T> = Vec::with_capacity(n + 1);
+    pred.push(zero);
+    for m in 1..=n {
+        let mut p = zero;
+        for j in 1..=m {
+            p += theta[m - 1][j - 1] * (x[m - j] - pred[m - j]);
+        }
+        pred.push(p);
+    }
+    Ok((pred, v))
+}
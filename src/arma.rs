@@ -0,0 +1,182 @@
+use anyhow::Result;
+
+use crate::{acf, linalg, poly};
+
+/// Calculate the psi-weights of an ARMA process, i.e. the coefficients of its infinite MA
+/// representation `x_t = e_t + psi_1 * e_t-1 + psi_2 * e_t-2 + ...`, like R's `ARMAtoMA`.
+/// For seasonal models, pass the expanded polynomials, see `util::expand_seasonal_ar` and
+/// `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector.
+/// * `&theta` - MA parameter vector.
+/// * `k` - Number of weights.
+///
+/// # Returns
+///
+/// * Output vector of length k containing psi_1, ..., psi_k. psi_0 is always 1.
+///
+/// # Example
+///
+/// ```
+/// use arima::arma;
+/// let psi = arma::psi_weights(&[0.5], &[0.4], 3);
+/// assert!((psi[0] - 0.9).abs() < 1.0e-12);
+/// assert!((psi[1] - 0.45).abs() < 1.0e-12);
+/// assert!((psi[2] - 0.225).abs() < 1.0e-12);
+/// ```
+pub fn psi_weights(phi: &[f64], theta: &[f64], k: usize) -> Vec<f64> {
+    // psi_j = theta_j + sum_i phi_i * psi_j-i with psi_0 = 1
+    let mut psi = vec![1.0; k + 1];
+    for j in 1..=k {
+        let mut v = if j <= theta.len() { theta[j - 1] } else { 0.0 };
+        for i in 1..=j.min(phi.len()) {
+            v += phi[i - 1] * psi[j - i];
+        }
+        psi[j] = v;
+    }
+    psi.split_off(1)
+}
+
+/// Calculate the pi-weights of an ARMA process, i.e. the coefficients of its infinite AR
+/// representation `x_t = pi_1 * x_t-1 + pi_2 * x_t-2 + ... + e_t`. The MA part has to be
+/// invertible for the weights to converge. For seasonal models, pass the expanded
+/// polynomials, see `util::expand_seasonal_ar` and `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector.
+/// * `&theta` - MA parameter vector.
+/// * `k` - Number of weights.
+///
+/// # Returns
+///
+/// * Output vector of length k containing pi_1, ..., pi_k.
+///
+/// # Example
+///
+/// ```
+/// use arima::arma;
+/// // an invertible MA(1) process is an AR process with pi_j = -(-theta)^j
+/// let pi = arma::pi_weights(&[], &[0.5], 3);
+/// assert!((pi[0] - 0.5).abs() < 1.0e-12);
+/// assert!((pi[1] + 0.25).abs() < 1.0e-12);
+/// assert!((pi[2] - 0.125).abs() < 1.0e-12);
+/// ```
+pub fn pi_weights(phi: &[f64], theta: &[f64], k: usize) -> Vec<f64> {
+    // 1 - sum_j pi_j B^j = phi(B) / theta(B) are the negative psi-weights of the
+    // process with the roles of the polynomials swapped
+    let ar: Vec<f64> = theta.iter().map(|t| -t).collect();
+    let ma: Vec<f64> = phi.iter().map(|p| -p).collect();
+    psi_weights(&ar, &ma, k).iter().map(|p| -p).collect()
+}
+
+/// Calculate the theoretical auto-correlation function of an ARMA process, like R's
+/// `ARMAacf`. For seasonal models, pass the expanded polynomials, see
+/// `util::expand_seasonal_ar` and `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector. The AR part has to be stationary.
+/// * `&theta` - MA parameter vector.
+/// * `max_lag` - Calculate the ACF up to this lag.
+/// * `covariance` - If true, returns auto-covariances for an innovation variance of one.
+///   Multiply by the innovation variance to get the auto-covariances of the process.
+///   If false, returns auto-correlations.
+///
+/// # Returns
+///
+/// * Output vector of length max_lag+1.
+///
+/// # Example
+///
+/// ```
+/// use arima::arma;
+/// // the ACF of an AR(1) process decays geometrically
+/// let rho = arma::theoretical_acf(&[0.5], &[], 3, false).unwrap();
+/// assert!((rho[3] - 0.125).abs() < 1.0e-12);
+/// let gamma = arma::theoretical_acf(&[0.5], &[], 0, true).unwrap();
+/// assert!((gamma[0] - 1.0 / 0.75).abs() < 1.0e-12);
+/// ```
+pub fn theoretical_acf(
+    phi: &[f64],
+    theta: &[f64],
+    max_lag: usize,
+    covariance: bool,
+) -> Result<Vec<f64>> {
+    let p = phi.len();
+    let q = theta.len();
+    if !poly::is_stationary(phi) {
+        anyhow::bail!("AR part is not stationary");
+    }
+
+    // gamma_k - sum_i phi_i gamma_k-i = sum_j=k..q theta_j psi_j-k, with theta_0 = 1
+    let psi = psi_weights(phi, theta, q);
+    let rhs = |k: usize| -> f64 {
+        (k..=q)
+            .map(|j| {
+                let theta_j = if j == 0 { 1.0 } else { theta[j - 1] };
+                let psi_jk = if j == k { 1.0 } else { psi[j - k - 1] };
+                theta_j * psi_jk
+            })
+            .sum()
+    };
+
+    // the first p+1 equations determine gamma_0, ..., gamma_p
+    let mut a = vec![vec![0.0; p + 1]; p + 1];
+    for (k, row) in a.iter_mut().enumerate() {
+        row[k] += 1.0;
+        for i in 1..=p {
+            row[k.abs_diff(i)] -= phi[i - 1];
+        }
+    }
+    let b: Vec<f64> = (0..=p).map(rhs).collect();
+    let mut gamma = linalg::solve(&a, &b)?;
+
+    // the remaining lags follow from the recursion
+    gamma.truncate(max_lag + 1);
+    for k in gamma.len()..=max_lag {
+        let mut g = if k <= q { rhs(k) } else { 0.0 };
+        for i in 1..=p {
+            g += phi[i - 1] * gamma[k - i];
+        }
+        gamma.push(g);
+    }
+
+    if !covariance {
+        let gamma0 = gamma[0];
+        for g in gamma.iter_mut() {
+            *g /= gamma0;
+        }
+    }
+    Ok(gamma)
+}
+
+/// Calculate the theoretical partial auto-correlation function of an ARMA process, like
+/// R's `ARMAacf` with `pacf = TRUE`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector. The AR part has to be stationary.
+/// * `&theta` - MA parameter vector.
+/// * `max_lag` - Calculate the PACF up to this lag.
+///
+/// # Returns
+///
+/// * Output vector of length max_lag containing the partial auto-correlations of the lags
+///   1, ..., max_lag.
+///
+/// # Example
+///
+/// ```
+/// use arima::arma;
+/// // the PACF of an AR(2) process cuts off after lag 2
+/// let pacf = arma::theoretical_pacf(&[0.5, 0.2], &[], 4).unwrap();
+/// assert!((pacf[1] - 0.2).abs() < 1.0e-12);
+/// assert!(pacf[2].abs() < 1.0e-12 && pacf[3].abs() < 1.0e-12);
+/// ```
+pub fn theoretical_pacf(phi: &[f64], theta: &[f64], max_lag: usize) -> Result<Vec<f64>> {
+    let rho = theoretical_acf(phi, theta, max_lag, false)?;
+    acf::pacf_rho_cov0(&rho, 1.0, Some(max_lag))
+}
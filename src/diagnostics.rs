@@ -0,0 +1,109 @@
+use anyhow::Result;
+
+use crate::{acf, stats};
+
+/// Result of a portmanteau test for autocorrelation.
+#[derive(Debug, Clone)]
+pub struct PortmanteauTest {
+    /// Value of the test statistic.
+    pub statistic: f64,
+    /// Degrees of freedom of the chi-squared distribution, i.e. the number of lags minus
+    /// the number of fitted ARMA coefficients.
+    pub df: usize,
+    /// p-value of the test statistic.
+    pub p_value: f64,
+    /// Number of lags of the auto-correlations in the statistic.
+    pub lags: usize,
+}
+
+/// Residual diagnostics of a fitted model, see `FittedArima::diagnostics`.
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    /// Ljung-Box test of the residuals.
+    pub ljung_box: PortmanteauTest,
+    /// Box-Pierce test of the residuals.
+    pub box_pierce: PortmanteauTest,
+    /// Auto-correlations of the residuals for the lags 0, ..., h.
+    pub residual_acf: Vec<f64>,
+}
+
+/// Ljung-Box test for autocorrelation. The statistic is
+/// `Q = n (n + 2) sum_k=1..h rho_k^2 / (n - k)`, which is asymptotically chi-squared
+/// distributed with `h - fitdf` degrees of freedom under the null hypothesis that the
+/// series is white noise.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries, e.g. the residuals of a fitted model.
+/// * `lags` - Number of lags h.
+/// * `fitdf` - Number of fitted ARMA coefficients if `x` are residuals, zero otherwise.
+///
+/// # Returns
+///
+/// * Test statistic, degrees of freedom and p-value.
+///
+/// # Example
+///
+/// ```
+/// use arima::diagnostics;
+/// let x: Vec<f64> = (0..100).map(|t| (t as f64 / 5.0).sin()).collect();
+/// let result = diagnostics::ljung_box(&x, 10, 0).unwrap();
+/// assert_eq!(result.df, 10);
+/// assert!(result.p_value < 0.01);
+/// ```
+pub fn ljung_box(x: &[f64], lags: usize, fitdf: usize) -> Result<PortmanteauTest> {
+    let n = x.len() as f64;
+    portmanteau(x, lags, fitdf, |k| n * (n + 2.0) / (n - k as f64))
+}
+
+/// Box-Pierce test for autocorrelation. The statistic is `Q = n sum_k=1..h rho_k^2`, which
+/// is asymptotically chi-squared distributed with `h - fitdf` degrees of freedom under
+/// the null hypothesis that the series is white noise. The Ljung-Box test has better
+/// small sample properties.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries, e.g. the residuals of a fitted model.
+/// * `lags` - Number of lags h.
+/// * `fitdf` - Number of fitted ARMA coefficients if `x` are residuals, zero otherwise.
+///
+/// # Returns
+///
+/// * Test statistic, degrees of freedom and p-value.
+///
+/// # Example
+///
+/// ```
+/// use arima::diagnostics;
+/// let x: Vec<f64> = (0..100).map(|t| (t as f64 / 5.0).sin()).collect();
+/// let result = diagnostics::box_pierce(&x, 10, 0).unwrap();
+/// assert!(result.p_value < 0.01);
+/// ```
+pub fn box_pierce(x: &[f64], lags: usize, fitdf: usize) -> Result<PortmanteauTest> {
+    let n = x.len() as f64;
+    portmanteau(x, lags, fitdf, |_| n)
+}
+
+/// Portmanteau statistic `sum_k=1..h weight(k) rho_k^2` with chi-squared p-value.
+fn portmanteau<W: Fn(usize) -> f64>(
+    x: &[f64],
+    lags: usize,
+    fitdf: usize,
+    weight: W,
+) -> Result<PortmanteauTest> {
+    if lags == 0 || lags >= x.len() {
+        anyhow::bail!("Number of lags must be positive and smaller than the series length");
+    }
+    if fitdf >= lags {
+        anyhow::bail!("Number of lags must be larger than the number of fitted coefficients");
+    }
+    let rho = acf::acf(x, Some(lags), false)?;
+    let statistic: f64 = (1..=lags).map(|k| weight(k) * rho[k] * rho[k]).sum();
+    let df = lags - fitdf;
+    Ok(PortmanteauTest {
+        statistic,
+        df,
+        p_value: stats::chi2_sf(statistic, df as f64),
+        lags,
+    })
+}
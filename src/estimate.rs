@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
+use num::complex::Complex;
 use num::Float;
 
 use std::cmp::min;
@@ -10,7 +11,10 @@ use std::ops::{Add, AddAssign, Div};
 use finitediff::FiniteDiff;
 use liblbfgs::lbfgs;
 
-use crate::{acf, util};
+use crate::model::{Convergence, ConvergenceStatus, FittedArima, SeasonalOrder};
+use crate::spectrum;
+use crate::unitroot::{self, UnitRootTest};
+use crate::{acf, kalman, linalg, poly, util};
 
 /// Calculate residuals given a time series, an intercept, and ARMA parameters
 /// phi and theta. Any differencing and centering should be done before.
@@ -33,7 +37,7 @@ use crate::{acf, util};
 ///
 /// ```
 /// use arima::estimate;
-/// let x = [1.0, 1.2, 1.4, 1.6];
+/// let x: [f64; 4] = [1.0, 1.2, 1.4, 1.6];
 /// let res = estimate::residuals(&x, 0.0, Some(&[0.6, 0.4]), Some(&[0.3])).unwrap();
 /// assert!((res[0] - 0.00).abs() < 1.0e-7);
 /// assert!((res[1] - 0.00).abs() < 1.0e-7);
@@ -73,9 +77,370 @@ pub fn residuals<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div
     Ok(residuals)
 }
 
-/// Fit an ARIMA model. Returns the fitted coefficients.
+/// Method used to estimate the coefficients in `fit_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Method {
+    /// Minimize the conditional sum of squares (CSS).
+    #[default]
+    Css,
+    /// Maximize the exact Gaussian likelihood, computed with a Kalman filter.
+    Ml,
+    /// Use the CSS estimates as starting values to maximize the exact likelihood.
+    CssMl,
+    /// Minimize the Whittle approximation of the likelihood in the frequency domain. The
+    /// periodogram is computed once, which makes this much faster than CSS for long series.
+    /// The AR and MA parts are always constrained to be stationary and invertible, and the
+    /// regression coefficients are kept at their initial values. Non-invertible initial MA
+    /// coefficients are inverted first.
+    Whittle,
+}
+
+/// Gradient of the CSS objective used by the optimizer in `fit_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gradient {
+    /// Differentiate the residual recursion analytically.
+    #[default]
+    Analytic,
+    /// Forward finite differences of the objective, e.g. as a cross-check.
+    ForwardDiff,
+}
+
+/// Options for fitting an ARIMA model with `fit_with_options`.
+///
+/// # Example
+///
+/// ```
+/// use arima::estimate::{FitOptions, Method};
+/// let options = FitOptions::new()
+///     .with_method(Method::CssMl)
+///     .with_seasonal(1, 1, 0, 12)
+///     .with_max_iterations(500);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FitOptions {
+    method: Method,
+    seasonal: SeasonalOrder,
+    xreg: Vec<Vec<f64>>,
+    transform_pars: bool,
+    gradient: Gradient,
+    max_iterations: usize,
+    gradient_tolerance: f64,
+    function_tolerance: f64,
+    init: Option<Vec<f64>>,
+    fixed: Vec<Option<f64>>,
+    include_mean: bool,
+    require_convergence: bool,
+}
+
+impl Default for FitOptions {
+    fn default() -> FitOptions {
+        FitOptions {
+            method: Method::default(),
+            seasonal: SeasonalOrder::default(),
+            xreg: Vec::new(),
+            transform_pars: false,
+            gradient: Gradient::default(),
+            max_iterations: 200,
+            gradient_tolerance: 1.0e-5,
+            function_tolerance: 0.0,
+            init: None,
+            fixed: Vec::new(),
+            include_mean: true,
+            require_convergence: false,
+        }
+    }
+}
+
+impl FitOptions {
+    /// Create options with the defaults used by `fit`.
+    pub fn new() -> FitOptions {
+        Default::default()
+    }
+
+    /// Set the estimation method. Defaults to `Method::Css`.
+    pub fn with_method(mut self, method: Method) -> FitOptions {
+        self.method = method;
+        self
+    }
+
+    /// Add a seasonal part to the model, i.e. fit a SARIMA(p, d, q)(P, D, Q)_s model.
+    ///
+    /// # Arguments
+    ///
+    /// * `ar` - Order of the seasonal AR coefficients (P).
+    /// * `d` - Order of seasonal differencing (D).
+    /// * `ma` - Order of the seasonal MA coefficients (Q).
+    /// * `period` - Number of observations per season (s).
+    pub fn with_seasonal(mut self, ar: usize, d: usize, ma: usize, period: usize) -> FitOptions {
+        self.seasonal = SeasonalOrder {
+            p: ar,
+            d,
+            q: ma,
+            period,
+        };
+        self
+    }
+
+    /// Add exogenous regressors, i.e. fit a regression with ARIMA errors
+    /// `x_t = beta_1 * z_1,t + ... + beta_k * z_k,t + u_t`, where `u_t` follows the
+    /// (seasonal) ARIMA model. The regression coefficients are estimated jointly with the
+    /// ARMA coefficients. Any differencing is applied to both `x` and the regressors.
+    ///
+    /// # Arguments
+    ///
+    /// * `xreg` - Regressors, one vector of the same length as the timeseries per regressor.
+    pub fn with_xreg(mut self, xreg: Vec<Vec<f64>>) -> FitOptions {
+        self.xreg = xreg;
+        self
+    }
+
+    /// Constrain the estimates to stationary AR and invertible MA polynomials. The optimizer
+    /// then works on partial autocorrelations, which are mapped to the AR coefficients with
+    /// the transform of Jones (1980) and to the MA coefficients with the analogous transform
+    /// of Monahan (1984). Seasonal polynomials are constrained separately.
+    ///
+    /// Defaults to `false`, in which case only the AR coefficients of the exact likelihood
+    /// are constrained, as the likelihood is not defined for non-stationary models. The
+    /// Whittle likelihood always constrains the AR and MA coefficients.
+    pub fn with_transform_pars(mut self, transform_pars: bool) -> FitOptions {
+        self.transform_pars = transform_pars;
+        self
+    }
+
+    /// Set how the gradient of the CSS objective is computed. Defaults to
+    /// `Gradient::Analytic`. The exact likelihood always uses finite differences.
+    pub fn with_gradient(mut self, gradient: Gradient) -> FitOptions {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Set the maximum number of L-BFGS iterations of each optimization. With zero
+    /// iterations, the estimates are the initial values. Defaults to 200.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> FitOptions {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the convergence tolerance on the gradient. The optimization stops when
+    /// `||g|| < tolerance * max(1, ||x||)`. Defaults to 1e-5.
+    pub fn with_gradient_tolerance(mut self, tolerance: f64) -> FitOptions {
+        self.gradient_tolerance = tolerance;
+        self
+    }
+
+    /// Set the convergence tolerance on the objective. The optimization stops when the
+    /// relative decrease of the objective in one iteration is below the tolerance.
+    /// Defaults to 0, i.e. no test on the objective.
+    pub fn with_function_tolerance(mut self, tolerance: f64) -> FitOptions {
+        self.function_tolerance = tolerance;
+        self
+    }
+
+    /// Start the optimization at the given coefficients instead of the internal initial
+    /// guesses, e.g. to warm-start from a previous fit. The coefficients are in the order of
+    /// `FittedArima::coef`. With the exact likelihood or `with_transform_pars`, the initial
+    /// AR part has to be stationary and the initial MA part invertible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate::{self, FitOptions};
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let options = FitOptions::new().with_init(model.coef());
+    /// let refit = estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+    /// assert!((refit.phi()[0] - model.phi()[0]).abs() < 1.0e-4);
+    /// ```
+    pub fn with_init(mut self, init: Vec<f64>) -> FitOptions {
+        self.init = Some(init);
+        self
+    }
+
+    /// Fix some of the coefficients instead of estimating them, like the `fixed` argument
+    /// of R's `arima`. The mask is in the order of `FittedArima::coef`, where `Some(value)`
+    /// fixes the coefficient at the value and `None` estimates it. Fixing any AR (or MA)
+    /// coefficient of a polynomial turns off its transform to partial autocorrelations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate::{self, FitOptions};
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let options = FitOptions::new().with_fixed(vec![None, Some(0.0), None]);
+    /// let model = estimate::fit_with_options(&x, 2, 0, 0, &options).unwrap();
+    /// assert_eq!(model.phi()[0], 0.0);
+    /// ```
+    pub fn with_fixed(mut self, fixed: Vec<Option<f64>>) -> FitOptions {
+        self.fixed = fixed;
+        self
+    }
+
+    /// Include the intercept in the model. If `false`, the intercept is fixed at zero.
+    /// Defaults to `true`.
+    pub fn with_mean(mut self, include_mean: bool) -> FitOptions {
+        self.include_mean = include_mean;
+        self
+    }
+
+    /// Return an error instead of the model if the optimizer does not converge, see
+    /// `FittedArima::convergence`. Defaults to `false`.
+    pub fn with_require_convergence(mut self, require_convergence: bool) -> FitOptions {
+        self.require_convergence = require_convergence;
+        self
+    }
+}
+
+/// Layout of the coefficient vector: the intercept, followed by the AR, MA, seasonal AR,
+/// seasonal MA and regression coefficients.
+struct Spec {
+    p: usize,
+    q: usize,
+    seasonal: SeasonalOrder,
+    k: usize,
+}
+
+impl Spec {
+    fn n_coef(&self) -> usize {
+        1 + self.p + self.q + self.seasonal.p + self.seasonal.q + self.k
+    }
+
+    fn ar(&self) -> std::ops::Range<usize> {
+        1..1 + self.p
+    }
+
+    fn ma(&self) -> std::ops::Range<usize> {
+        1 + self.p..1 + self.p + self.q
+    }
+
+    fn sar(&self) -> std::ops::Range<usize> {
+        let start = 1 + self.p + self.q;
+        start..start + self.seasonal.p
+    }
+
+    fn sma(&self) -> std::ops::Range<usize> {
+        let start = 1 + self.p + self.q + self.seasonal.p;
+        start..start + self.seasonal.q
+    }
+
+    fn xreg(&self) -> std::ops::Range<usize> {
+        let start = 1 + self.p + self.q + self.seasonal.p + self.seasonal.q;
+        start..start + self.k
+    }
+
+    /// Remove the regression effects from the (differenced) series x.
+    fn regress(&self, x: &[f64], xreg: &[Vec<f64>], coef: &[f64]) -> Vec<f64> {
+        let mut y = x.to_vec();
+        for (beta, z) in coef[self.xreg()].iter().zip(xreg) {
+            for (yt, zt) in y.iter_mut().zip(z) {
+                *yt -= beta * zt;
+            }
+        }
+        y
+    }
+
+    /// Conditional sum of squares and its gradient with respect to the coefficients.
+    /// The derivatives of the residuals follow the recursion
+    /// `de_t = f_t - theta*_1 de_t-1 - ... - theta*_q de_t-q`, where `theta*` is the
+    /// expanded MA polynomial and `f_t` the derivative of the right-hand side of
+    /// `e_t = y_t - intercept - sum_j phi*_j y_t-j - sum_j theta*_j e_t-j` for fixed past
+    /// residuals.
+    fn css_gradient(&self, x: &[f64], xreg: &[Vec<f64>], coef: &[f64]) -> (f64, Vec<f64>) {
+        let (phi, theta) = self.expand(coef);
+        let y = self.regress(x, xreg, coef);
+        let e = match residuals(&y, coef[0], Some(&phi), Some(&theta)) {
+            Ok(e) => e,
+            Err(_) => return (f64::MAX, vec![0.0; coef.len()]),
+        };
+        let css: f64 = e.iter().map(|v| v * v).sum();
+
+        let s = self.seasonal.period;
+        let (ar, ma) = (&coef[self.ar()], &coef[self.ma()]);
+        let (sar, sma) = (&coef[self.sar()], &coef[self.sma()]);
+
+        // derivatives of the expanded polynomials as (lag, weight) pairs, e.g.
+        // d phi*_j / d phi_i = 1 for j = i and -Phi_k for j = i + s*k
+        let lags = |i: usize, step: usize, other: &[f64], sign: f64| {
+            let mut w = vec![(i, 1.0)];
+            w.extend(
+                other
+                    .iter()
+                    .enumerate()
+                    .map(|(k, c)| (i + step * (k + 1), sign * c)),
+            );
+            w
+        };
+
+        let n = y.len();
+        let start = phi.len();
+        let mut grad = vec![0.0; coef.len()];
+        let mut de = vec![0.0; n];
+        for (i, g) in grad.iter_mut().enumerate() {
+            // derivative of the right-hand side for fixed past residuals
+            let forcing: Box<dyn Fn(usize) -> f64> = if i == 0 {
+                Box::new(|_| -1.0)
+            } else if self.ar().contains(&i) {
+                let w = lags(i - self.ar().start + 1, s, sar, -1.0);
+                let y = &y;
+                Box::new(move |t| -w.iter().map(|(l, c)| c * y[t - l]).sum::<f64>())
+            } else if self.sar().contains(&i) {
+                let w = lags(s * (i - self.sar().start + 1), 1, ar, -1.0);
+                let y = &y;
+                Box::new(move |t| -w.iter().map(|(l, c)| c * y[t - l]).sum::<f64>())
+            } else if self.ma().contains(&i) {
+                let w = lags(i - self.ma().start + 1, s, sma, 1.0);
+                let e = &e;
+                Box::new(move |t| {
+                    -w.iter()
+                        .filter(|(l, _)| *l <= t)
+                        .map(|(l, c)| c * e[t - l])
+                        .sum::<f64>()
+                })
+            } else if self.sma().contains(&i) {
+                let w = lags(s * (i - self.sma().start + 1), 1, ma, 1.0);
+                let e = &e;
+                Box::new(move |t| {
+                    -w.iter()
+                        .filter(|(l, _)| *l <= t)
+                        .map(|(l, c)| c * e[t - l])
+                        .sum::<f64>()
+                })
+            } else {
+                let z = &xreg[i - self.xreg().start];
+                let phi = &phi;
+                Box::new(move |t| {
+                    -z[t]
+                        + phi
+                            .iter()
+                            .enumerate()
+                            .map(|(j, c)| c * z[t - j - 1])
+                            .sum::<f64>()
+                })
+            };
+
+            for t in start..n {
+                let mut v = forcing(t);
+                for j in 0..theta.len().min(t) {
+                    v -= theta[j] * de[t - j - 1];
+                }
+                de[t] = v;
+                *g += 2.0 * e[t] * v;
+            }
+        }
+        (css, grad)
+    }
+
+    /// Expanded AR and MA polynomials of the (seasonal) ARMA model.
+    fn expand(&self, coef: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let s = self.seasonal.period;
+        let phi = util::expand_seasonal_ar(&coef[self.ar()], &coef[self.sar()], s);
+        let theta = util::expand_seasonal_ma(&coef[self.ma()], &coef[self.sma()], s);
+        (phi, theta)
+    }
+}
+
+/// Fit an ARIMA model. Returns the fitted model.
 /// This method uses the L-BFGS algorithm and the conditional sum of squares (CSS)
-/// as the objective function.
+/// as the objective function. See `fit_with_options` for other estimation methods.
 ///
 /// # Arguments
 ///
@@ -86,47 +451,195 @@ pub fn residuals<T: Float + From<u32> + From<f64> + Copy + Add + AddAssign + Div
 ///
 /// # Returns
 ///
-/// * ARIMA coefficients minimizing the conditional sum of squares (CSS).
+/// * Fitted model with the ARIMA coefficients minimizing the conditional sum of squares (CSS).
 ///
 /// # Example
 ///
 /// ```
 /// use arima::estimate;
 /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0];
-/// let coef = estimate::fit(&x, 0, 0, 1).unwrap();
-/// assert!((coef[0] - 1.2051).abs() < 1.0e-3); // intercept
-/// assert!((coef[1] - 0.5637).abs() < 1.0e-3); // phi_1
+/// let model = estimate::fit(&x, 0, 0, 1).unwrap();
+/// assert!((model.intercept() - 1.2051).abs() < 1.0e-3);
+/// assert!((model.theta()[0] - 0.5637).abs() < 1.0e-3);
 /// ```
 pub fn fit<T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign + Div + Debug>(
     x: &[T],
     ar: usize,
     d: usize,
     ma: usize,
-) -> Result<Vec<f64>> {
+) -> Result<FittedArima> {
+    fit_with_options(x, ar, d, ma, &FitOptions::default())
+}
+
+/// Fit an ARIMA model with the given options. Returns the fitted model.
+/// With `Method::Ml` and `Method::CssMl`, the exact likelihood of the differenced series
+/// is computed by `kalman::arma_loglik` and maximized with the L-BFGS algorithm. With
+/// `Method::Whittle`, the likelihood is approximated in the frequency domain from the
+/// periodogram of the differenced series, see `spectrum::periodogram`.
+/// Seasonal models are fitted on the expanded AR and MA polynomials, see
+/// `util::expand_seasonal_ar` and `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `ar` - Order of the AR coefficients.
+/// * `d` - Order of differencing.
+/// * `ma` - Order of the MA coefficients.
+/// * `&options` - Estimation options.
+///
+/// # Returns
+///
+/// * Fitted model with the estimated ARIMA coefficients.
+///
+/// # Example
+///
+/// ```
+/// use arima::estimate::{self, FitOptions, Method};
+/// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+/// let options = FitOptions::new().with_method(Method::Ml);
+/// let model = estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+/// assert!(model.phi()[0] > 0.0 && model.phi()[0] < 1.0);
+/// ```
+pub fn fit_with_options<
+    T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign + Div + Debug,
+>(
+    x: &[T],
+    ar: usize,
+    d: usize,
+    ma: usize,
+    options: &FitOptions,
+) -> Result<FittedArima> {
+    let seasonal = options.seasonal;
+    if seasonal.period == 0 && seasonal.p + seasonal.d + seasonal.q > 0 {
+        anyhow::bail!("Seasonal period must be positive");
+    }
+
     // Convert into f64 as the optimizer functions only support f64
     let mut x64: Vec<f64> = Vec::new();
     for a in x {
         x64.push((*a).into());
     }
-    let mut x = x64;
+    let x_orig = x64;
+
+    // The differenced series has to be longer than the expanded AR and MA polynomials
+    let s = seasonal.period;
+    let n_diff = x_orig.len().saturating_sub(d + seasonal.d * s);
+    if n_diff <= ar + seasonal.p * s || n_diff <= ma + seasonal.q * s {
+        anyhow::bail!("Time series is too short for the order of the model");
+    }
 
-    if d > 0 {
-        x = util::diff(&x, d);
+    let difference = |x: &[f64]| {
+        let x = util::diff(x, d);
+        util::diff_seasonal(&x, seasonal.d, seasonal.period)
+    };
+    let x = difference(&x_orig);
+
+    let xreg = &options.xreg;
+    if xreg.iter().any(|z| z.len() != x_orig.len()) {
+        anyhow::bail!("Regressors must have the same length as the timeseries");
     }
-    let x = x;
+    let zd: Vec<Vec<f64>> = xreg.iter().map(|z| difference(z)).collect();
 
-    let total_size = 1 + ar + ma;
+    let spec = Spec {
+        p: ar,
+        q: ma,
+        seasonal,
+        k: xreg.len(),
+    };
+    let total_size = spec.n_coef();
+
+    if options.init.as_ref().is_some_and(|c| c.len() != total_size) {
+        anyhow::bail!("Expected {} initial coefficients", total_size);
+    }
+    if !options.fixed.is_empty() && options.fixed.len() != total_size {
+        anyhow::bail!(
+            "Expected {} items in the mask of fixed coefficients",
+            total_size
+        );
+    }
+    if options.gradient_tolerance < 0.0 || options.function_tolerance < 0.0 {
+        anyhow::bail!("Tolerances must not be negative");
+    }
+
+    // Coefficients fixed by the user, including the intercept of a model without mean
+    let mut fixed: Vec<Option<f64>> = if options.fixed.is_empty() {
+        vec![None; total_size]
+    } else {
+        options.fixed.clone()
+    };
+    if !options.include_mean {
+        fixed[0] = Some(0.0);
+    }
+    let free: Vec<usize> = (0..total_size).filter(|&i| fixed[i].is_none()).collect();
+
+    // Initial coefficients
+    let mut coef: Vec<f64> = vec![0.0; total_size];
+
+    // Initial guess for the regression coefficients: Ordinary least squares
+    if spec.k > 0 {
+        let mut columns = vec![vec![1.0; x.len()]];
+        columns.extend(zd.iter().cloned());
+        let beta = linalg::lstsq(&columns, &x).context("Regressors are collinear")?;
+        coef[spec.xreg()].copy_from_slice(&beta[1..]);
+    }
+    let y = spec.regress(&x, &zd, &coef);
+
+    // Initial guess for the intercept, AR and MA coefficients: Hannan-Rissanen estimates, or
+    // the Yule-Walker estimates of an AR model if these are not available, not stationary
+    // or not invertible. The seasonal coefficients start at zero.
+    let (intercept, phi, theta) = initial_arma(&y, ar, ma);
+    coef[0] = intercept;
+    coef[spec.ar()].copy_from_slice(&phi);
+    coef[spec.ma()].copy_from_slice(&theta);
+    let transform_pars = options.transform_pars;
+
+    if let Some(init) = &options.init {
+        coef.copy_from_slice(init);
+    }
+    for (c, value) in coef.iter_mut().zip(&fixed) {
+        if let Some(value) = value {
+            *c = *value;
+        }
+    }
+
+    // Polynomials that are optimized as partial autocorrelations. Polynomials with fixed
+    // coefficients are optimized directly.
+    let unfixed = |range: &std::ops::Range<usize>| range.clone().all(|i| fixed[i].is_none());
+    let ar_groups: Vec<std::ops::Range<usize>> = vec![spec.ar(), spec.sar()]
+        .into_iter()
+        .filter(|r| unfixed(r))
+        .collect();
+    let ma_groups: Vec<std::ops::Range<usize>> = if transform_pars {
+        vec![spec.ma(), spec.sma()]
+            .into_iter()
+            .filter(|r| unfixed(r))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Insert the free parameters into the full parameter vector `par`
+    let embed = |free_par: &[f64], par: &[f64]| {
+        let mut par = par.to_vec();
+        for (k, &i) in free.iter().enumerate() {
+            par[i] = free_par[k];
+        }
+        par
+    };
 
     // The objective is to minimize the conditional sum of squares (CSS),
     // i.e. the sum of the squared residuals
-    let f = |coef: &Vec<f64>| {
+    let css = |coef: &[f64]| {
         assert_eq!(coef.len(), total_size);
 
         let intercept = coef[0];
-        let phi = &coef[1..ar + 1];
-        let theta = &coef[ar + 1..];
+        let (phi, theta) = spec.expand(coef);
+        let y = spec.regress(&x, &zd, coef);
 
-        let residuals = residuals(&x, intercept, Some(phi), Some(theta)).unwrap();
+        let residuals = match residuals(&y, intercept, Some(&phi), Some(&theta)) {
+            Ok(residuals) => residuals,
+            Err(_) => return f64::MAX,
+        };
 
         let mut css: f64 = 0.0;
         for residual in &residuals {
@@ -134,133 +647,1092 @@ pub fn fit<T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign
         }
         css
     };
-    let g = |coef: &Vec<f64>| coef.forward_diff(&f);
 
-    // Initial coefficients
-    // Todo: These initial guesses are rather arbitrary.
-    let mut coef: Vec<f64> = Vec::new();
+    let css_gradient = |coef: &[f64]| match options.gradient {
+        Gradient::Analytic => spec.css_gradient(&x, &zd, coef),
+        Gradient::ForwardDiff => (css(coef), coef.to_vec().forward_diff(&|c| css(c))),
+    };
+
+    if options.method == Method::Whittle {
+        // The periodogram at the non-zero Fourier frequencies does not depend on the mean.
+        let y = spec.regress(&x, &zd, &coef);
+        let whittle = Whittle::new(&y)?;
 
-    // Initial guess for the intercept: First value of x
-    coef.push(util::mean(&x));
+        // The Whittle likelihood does not change if an MA root is replaced by its reciprocal,
+        // so the MA part is always constrained, and non-invertible initial values are
+        // inverted.
+        let ma_groups: Vec<std::ops::Range<usize>> = vec![spec.ma(), spec.sma()]
+            .into_iter()
+            .filter(|r| unfixed(r))
+            .collect();
+        let mut init = coef.clone();
+        for range in &ma_groups {
+            if !poly::is_invertible(&coef[range.clone()]) {
+                let theta = ma_invert(&coef[range.clone()]);
+                init[range.clone()].copy_from_slice(&theta);
+            }
+        }
+        let par = unconstrain(&init, &ar_groups, &ma_groups).context("Invalid initial values")?;
+
+        // only the ARMA coefficients enter the Whittle likelihood
+        let arma: Vec<usize> = free
+            .iter()
+            .copied()
+            .filter(|&i| i != 0 && !spec.xreg().contains(&i))
+            .collect();
+        let embed_arma = |arma_par: &[f64], par: &[f64]| {
+            let mut par = par.to_vec();
+            for (k, &i) in arma.iter().enumerate() {
+                par[i] = arma_par[k];
+            }
+            par
+        };
+        let f = |arma_par: &Vec<f64>| {
+            let coef = constrain(&embed_arma(arma_par, &par), &ar_groups, &ma_groups);
+            whittle.objective(&spec, &coef)
+        };
+        let mut arma_par: Vec<f64> = arma.iter().map(|&i| par[i]).collect();
+        let convergence = minimize(&mut arma_par, with_forward_diff(f), 0.1, options);
+        let mut coef = constrain(&embed_arma(&arma_par, &par), &ar_groups, &ma_groups);
+
+        let (phi, theta) = spec.expand(&coef);
+        if fixed[0].is_none() {
+            coef[0] = util::mean(&y) * (1.0 - phi.iter().sum::<f64>());
+        }
+        let residuals = residuals(&y, coef[0], Some(&phi), Some(&theta))?;
 
-    // Initial guess for the AR coefficients: Values of the PACF
-    if ar > 0 {
-        let pacf = acf::pacf(&x, Some(ar)).unwrap();
-        for p in pacf {
-            coef.push(p);
+        // negative Whittle log-likelihood, up to a constant. The intercept and the regression
+        // coefficients have no variance in this approximation.
+        let nll = |coef: &[f64]| whittle.len() as f64 * whittle.objective(&spec, coef);
+        let cov_arma = covariance(nll, &coef, &arma);
+        let mut cov = vec![vec![f64::NAN; free.len()]; free.len()];
+        for (a, i) in arma.iter().enumerate() {
+            for (b, j) in arma.iter().enumerate() {
+                let ii = free.iter().position(|k| k == i).unwrap();
+                let jj = free.iter().position(|k| k == j).unwrap();
+                cov[ii][jj] = cov_arma[a][b];
+            }
         }
+
+        let model = FittedArima::new(&x_orig, (ar, d, ma), seasonal, &coef, xreg, residuals)
+            .with_covariance(cov, &free);
+        return finish(model, Some(convergence), options);
+    }
+
+    let mut convergence = None;
+    if options.method != Method::Ml {
+        // optimize over the partial autocorrelations if constrained, see `constrain`.
+        // The gradient is mapped back with the Jacobian of the transform.
+        let (ar_t, ma_t): (&[_], &[_]) = if transform_pars {
+            (&ar_groups, &ma_groups)
+        } else {
+            (&[], &[])
+        };
+        let par = unconstrain(&coef, ar_t, ma_t).context("Invalid initial values")?;
+
+        let fg = |free_par: &[f64]| {
+            let par = embed(free_par, &par);
+            let (css, grad) = css_gradient(&constrain(&par, ar_t, ma_t));
+            let h = 1.0e-6;
+            let mut par_grad = grad.clone();
+            let mut pt = par.clone();
+            for range in ar_t.iter().chain(ma_t) {
+                for i in range.clone() {
+                    pt[i] = par[i] + h;
+                    let upper = constrain(&pt, ar_t, ma_t);
+                    pt[i] = par[i] - h;
+                    let lower = constrain(&pt, ar_t, ma_t);
+                    pt[i] = par[i];
+                    par_grad[i] = range
+                        .clone()
+                        .map(|j| grad[j] * (upper[j] - lower[j]) / (2.0 * h))
+                        .sum();
+                }
+            }
+            (css, free.iter().map(|&i| par_grad[i]).collect())
+        };
+        // The initial values are usually close to the estimates, so we take a small first
+        // step to not overshoot
+        let mut free_par: Vec<f64> = free.iter().map(|&i| par[i]).collect();
+        convergence = Some(minimize(&mut free_par, fg, 0.1, options));
+        coef = constrain(&embed(&free_par, &par), ar_t, ma_t);
+    }
+
+    if options.method == Method::Css {
+        let (phi, theta) = spec.expand(&coef);
+        let y = spec.regress(&x, &zd, &coef);
+        let residuals = residuals(&y, coef[0], Some(&phi), Some(&theta))?;
+
+        // negative conditional log-likelihood, up to a constant
+        let nobs = (x.len() - phi.len()) as f64;
+        let cov = covariance(|coef| 0.5 * nobs * css(coef).ln(), &coef, &free);
+
+        let model = FittedArima::new(&x_orig, (ar, d, ma), seasonal, &coef, xreg, residuals)
+            .with_covariance(cov, &free);
+        return finish(model, convergence, options);
     }
 
-    // Initial guess for the MA coefficients: 1.0
-    if ma > 0 {
-        coef.resize(coef.len() + ma, 1.0);
+    // The exact likelihood is parameterized with the mean instead of the intercept. The mean
+    // is optimized relative to the sample mean and in units of the standard deviation of x,
+    // the regression coefficients relative to their initial values and in units of the
+    // standard deviation of x per standard deviation of the regressor.
+    // The AR coefficients are optimized as (transformed) partial autocorrelations, so that
+    // the optimizer only sees stationary models.
+    let sd = |x: &[f64]| match acf::acf(x, Some(0), true) {
+        Ok(cov) if cov[0] > 0.0 => cov[0].sqrt(),
+        _ => 1.0,
+    };
+    let x_mean = util::mean(&y);
+    let x_sd = sd(&y);
+    let beta_init = coef[spec.xreg()].to_vec();
+    let beta_scale: Vec<f64> = zd.iter().map(|z| x_sd / sd(z)).collect();
+
+    // The initial values and the CSS estimates are ARMA coefficients with an intercept
+    let mut par = unconstrain(&coef, &ar_groups, &ma_groups)
+        .context("Invalid CSS estimates or initial values")?;
+    for j in spec.xreg() {
+        par[j] = 0.0;
+    }
+    let (phi, _theta) = spec.expand(&coef);
+    let mean = coef[0] / (1.0 - phi.iter().sum::<f64>());
+    par[0] = (mean - x_mean) / x_sd;
+
+    // Map the optimizer parameters back to the mean and the coefficients
+    let from_ml = |par: &[f64]| -> (f64, Vec<f64>) {
+        let mut coef = constrain(par, &ar_groups, &ma_groups);
+        for (i, j) in spec.xreg().enumerate() {
+            coef[j] = beta_init[i] + beta_scale[i] * par[j];
+        }
+        for (c, value) in coef.iter_mut().zip(&fixed) {
+            if let Some(value) = value {
+                *c = *value;
+            }
+        }
+        let (phi, _theta) = spec.expand(&coef);
+        let ar_sum = 1.0 - phi.iter().sum::<f64>();
+        let mean = match fixed[0] {
+            Some(intercept) => intercept / ar_sum,
+            None => x_mean + x_sd * par[0],
+        };
+        coef[0] = mean * ar_sum;
+        (mean, coef)
+    };
+
+    let f = |free_par: &Vec<f64>| {
+        let (mean, coef) = from_ml(&embed(free_par, &par));
+        let (phi, theta) = spec.expand(&coef);
+        let y: Vec<f64> = spec
+            .regress(&x, &zd, &coef)
+            .iter()
+            .map(|v| v - mean)
+            .collect();
+        match kalman::arma_loglik(&y, &phi, &theta) {
+            Ok(lik) => lik.objective(),
+            Err(_) => f64::MAX,
+        }
+    };
+    // The MA part of the likelihood has a stationary point on the unit circle, so we
+    // take a small first step to not run into it
+    let mut free_par: Vec<f64> = free.iter().map(|&i| par[i]).collect();
+    let convergence = minimize(&mut free_par, with_forward_diff(f), 0.1, options);
+
+    let (mean, coef) = from_ml(&embed(&free_par, &par));
+    let (phi, theta) = spec.expand(&coef);
+    let y: Vec<f64> = spec
+        .regress(&x, &zd, &coef)
+        .iter()
+        .map(|v| v - mean)
+        .collect();
+    let lik = kalman::arma_loglik(&y, &phi, &theta)?;
+
+    // negative exact log-likelihood in terms of the reported coefficients, up to a constant
+    let nll = |coef: &[f64]| {
+        let (phi, theta) = spec.expand(coef);
+        let mean = coef[0] / (1.0 - phi.iter().sum::<f64>());
+        let y: Vec<f64> = spec
+            .regress(&x, &zd, coef)
+            .iter()
+            .map(|v| v - mean)
+            .collect();
+        match kalman::arma_loglik(&y, &phi, &theta) {
+            Ok(lik) => lik.nobs as f64 * lik.objective(),
+            Err(_) => f64::NAN,
+        }
+    };
+    let cov = covariance(nll, &coef, &free);
+
+    let model = FittedArima::new(
+        &x_orig,
+        (ar, d, ma),
+        seasonal,
+        &coef,
+        xreg,
+        lik.residuals.clone(),
+    )
+    .with_likelihood(lik.sigma2(), lik.loglik(), lik.nobs)
+    .with_covariance(cov, &free);
+    finish(model, Some(convergence), options)
+}
+
+/// Estimate an ARIMA model with the Hannan-Rissanen procedure. This is much faster than
+/// `fit` as it only needs linear regressions, and the estimates are consistent, but less
+/// efficient than CSS or ML estimates. `fit` uses them as initial values.
+///
+/// The procedure fits a long AR model with the Durbin-Levinson recursion, see
+/// `acf::ar_dl_rho_cov`, and then regresses the demeaned differenced series on its lagged
+/// values and the lagged residuals of the long AR model. The order of the long AR model is
+/// `max(floor(ln(n)^2), 2 max(ar, ma))`.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `ar` - Order of the AR coefficients.
+/// * `d` - Order of differencing.
+/// * `ma` - Order of the MA coefficients.
+///
+/// # Returns
+///
+/// * Fitted model with the estimated ARIMA coefficients. The covariance matrix of the
+///   coefficients is not estimated.
+///
+/// # Example
+///
+/// ```
+/// use arima::estimate;
+/// let x: Vec<f64> = (0..200).map(|t| ((t * 7919) % 113) as f64).collect();
+/// let model = estimate::hannan_rissanen(&x, 1, 0, 1).unwrap();
+/// assert_eq!(model.phi().len(), 1);
+/// assert_eq!(model.theta().len(), 1);
+/// ```
+pub fn hannan_rissanen<T: Float + Into<f64> + Copy>(
+    x: &[T],
+    ar: usize,
+    d: usize,
+    ma: usize,
+) -> Result<FittedArima> {
+    let x_orig: Vec<f64> = x.iter().map(|&a| a.into()).collect();
+    if x_orig.len() <= d {
+        anyhow::bail!("Time series is too short for the order of differencing");
+    }
+    let y = util::diff(&x_orig, d);
+    let (intercept, phi, theta) = hannan_rissanen_coef(&y, ar, ma)?;
+    let residuals = residuals(&y, intercept, Some(&phi), Some(&theta))?;
+
+    let mut coef = vec![intercept];
+    coef.extend(&phi);
+    coef.extend(&theta);
+    Ok(FittedArima::new(
+        &x_orig,
+        (ar, d, ma),
+        SeasonalOrder::default(),
+        &coef,
+        &[],
+        residuals,
+    ))
+}
+
+/// Intercept, AR and MA coefficients of the Hannan-Rissanen estimates, see
+/// `hannan_rissanen`.
+fn hannan_rissanen_coef(y: &[f64], p: usize, q: usize) -> Result<(f64, Vec<f64>, Vec<f64>)> {
+    let n = y.len();
+    let mean = util::mean(y);
+    let z: Vec<f64> = y.iter().map(|v| v - mean).collect();
+
+    // residuals of a long AR model, only needed for the MA part
+    let m = if q > 0 {
+        ((n as f64).ln().powi(2).floor() as usize).max(2 * p.max(q))
+    } else {
+        0
+    };
+    let start = m + q.max(p);
+    if n < start + p + q + 1 {
+        anyhow::bail!("Time series is too short for the Hannan-Rissanen estimates");
+    }
+    let e = if q > 0 {
+        let rho = acf::acf(&z, Some(m), false)?;
+        let cov0 = acf::acf(&z, Some(0), true)?[0];
+        let (long_ar, _var) = acf::ar_dl_rho_cov(&rho, cov0, Some(m))?;
+        residuals(&z, 0.0, Some(&long_ar), None)?
+    } else {
+        Vec::new()
+    };
+
+    // regress z_t on z_t-1, ..., z_t-p and e_t-1, ..., e_t-q
+    let mut columns: Vec<Vec<f64>> = Vec::with_capacity(p + q);
+    for i in 1..=p {
+        columns.push((start..n).map(|t| z[t - i]).collect());
+    }
+    for j in 1..=q {
+        columns.push((start..n).map(|t| e[t - j]).collect());
+    }
+    let coef = if columns.is_empty() {
+        Vec::new()
+    } else {
+        linalg::lstsq(&columns, &z[start..])?
+    };
+    let phi = coef[..p].to_vec();
+    let theta = coef[p..].to_vec();
+    let intercept = mean * (1.0 - phi.iter().sum::<f64>());
+    Ok((intercept, phi, theta))
+}
+
+/// Initial intercept, AR and MA coefficients for `fit_with_options`: the Hannan-Rissanen
+/// estimates if they are stationary and invertible, and the Yule-Walker estimates of the
+/// AR part with a zero MA part otherwise.
+fn initial_arma(y: &[f64], p: usize, q: usize) -> (f64, Vec<f64>, Vec<f64>) {
+    if let Ok((intercept, phi, theta)) = hannan_rissanen_coef(y, p, q) {
+        if poly::is_stationary(&phi) && poly::is_invertible(&theta) {
+            return (intercept, phi, theta);
+        }
+    }
+    let mean = util::mean(y);
+    let phi = if p > 0 && y.len() > p {
+        acf::ar(y, Some(p))
+            .map(|(phi, _var)| phi)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let mut phi = phi;
+    phi.resize(p, 0.0);
+    let intercept = mean * (1.0 - phi.iter().sum::<f64>());
+    (intercept, phi, vec![0.0; q])
+}
+
+/// Periodogram of a series at the points `z_j = exp(-2 pi i f_j)` of the Fourier
+/// frequencies `f_j`, for evaluating the Whittle likelihood. The Nyquist frequency of series
+/// with an even length is left out.
+struct Whittle {
+    z: Vec<Complex<f64>>,
+    pgram: Vec<f64>,
+}
+
+impl Whittle {
+    fn new(x: &[f64]) -> Result<Whittle> {
+        let mut pgram = spectrum::periodogram(x)?;
+        if pgram.freq.last() == Some(&0.5) {
+            pgram.freq.pop();
+            pgram.spec.pop();
+        }
+        if pgram.freq.is_empty() {
+            anyhow::bail!("Time series is too short for the Whittle likelihood");
+        }
+        let z = pgram
+            .freq
+            .iter()
+            .map(|f| Complex::from_polar(1.0, -2.0 * std::f64::consts::PI * f))
+            .collect();
+        Ok(Whittle {
+            z,
+            pgram: pgram.spec,
+        })
+    }
+
+    /// Objective `ln(mean_j(I_j / g_j)) + mean_j(ln(g_j))` of the ARMA model `coef` for the
+    /// periodogram values `I_j`, where `g_j = |theta(z_j)|^2 / |phi(z_j)|^2` is the spectral
+    /// density for unit innovation variance. The innovation variance is concentrated out.
+    fn objective(&self, spec: &Spec, coef: &[f64]) -> f64 {
+        let (phi, theta) = spec.expand(coef);
+        // 1 + sign * sum_k c_k z^k by Horner's scheme
+        let eval = |c: &[f64], sign: f64, z: Complex<f64>| {
+            let mut v = Complex::new(0.0, 0.0);
+            for ck in c.iter().rev() {
+                v = (v + sign * ck) * z;
+            }
+            v + 1.0
+        };
+        let mut sum_ratio = 0.0;
+        let mut sum_log_g = 0.0;
+        for (z, i) in self.z.iter().zip(&self.pgram) {
+            let g = eval(&theta, 1.0, *z).norm_sqr() / eval(&phi, -1.0, *z).norm_sqr();
+            if !g.is_finite() || g <= 0.0 {
+                return f64::MAX;
+            }
+            sum_ratio += i / g;
+            sum_log_g += g.ln();
+        }
+        let m = self.z.len() as f64;
+        (sum_ratio / m).ln() + sum_log_g / m
+    }
+
+    /// Number of frequencies.
+    fn len(&self) -> usize {
+        self.z.len()
+    }
+}
+
+/// Attach the convergence report to the fitted model and check it.
+fn finish(
+    model: FittedArima,
+    convergence: Option<Convergence>,
+    options: &FitOptions,
+) -> Result<FittedArima> {
+    let model = match convergence {
+        Some(convergence) => model.with_convergence(convergence),
+        None => model,
+    };
+    let convergence = model.convergence();
+    if options.require_convergence && !convergence.converged() {
+        anyhow::bail!(
+            "Optimizer did not converge: {:?} after {} iterations, gradient norm {:e}",
+            convergence.status,
+            convergence.iterations,
+            convergence.gradient_norm
+        );
+    }
+    check_roots(&model);
+    Ok(model)
+}
+
+/// Warn if the estimated AR part is not stationary or the MA part is not invertible,
+/// reporting the smallest root modulus. Both polynomials need all roots outside the unit circle.
+fn check_roots(model: &FittedArima) {
+    let s = model.seasonal_order().period;
+    let phi = util::expand_seasonal_ar(model.phi(), model.seasonal_phi(), s);
+    let theta = util::expand_seasonal_ma(model.theta(), model.seasonal_theta(), s);
+    if !poly::is_stationary(&phi) {
+        let m = model.ar_root_moduli()[0];
+        tracing::warn!("AR part is not stationary, smallest root modulus {:.4}", m);
+    }
+    if !poly::is_invertible(&theta) {
+        let m = model.ma_root_moduli()[0];
+        tracing::warn!("MA part is not invertible, smallest root modulus {:.4}", m);
+    }
+    if !model.cancelling_roots(0.05).is_empty() {
+        tracing::warn!("AR and MA roots nearly cancel, the model may be over-parameterized");
+    }
+}
+
+/// Asymptotic covariance matrix of the free coefficients, i.e. the inverse of the Hessian
+/// of the negative log-likelihood `nll` at the estimate `coef` with respect to the
+/// coefficients with the indices `free`. The entries are NaN if the Hessian is singular.
+fn covariance<F: Fn(&[f64]) -> f64>(nll: F, coef: &[f64], free: &[usize]) -> Vec<Vec<f64>> {
+    let n = free.len();
+    let f = |free_coef: &[f64]| {
+        let mut c = coef.to_vec();
+        for (k, &i) in free.iter().enumerate() {
+            c[i] = free_coef[k];
+        }
+        nll(&c)
+    };
+    let free_coef: Vec<f64> = free.iter().map(|&i| coef[i]).collect();
+    let hessian = hessian(f, &free_coef);
+    linalg::inverse(&hessian).unwrap_or_else(|_| vec![vec![f64::NAN; n]; n])
+}
+
+/// Hessian of `f` at `x` with central differences. The steps are relative to the magnitude
+/// of the coefficients and much larger than the fixed steps of `finitediff`, which are
+/// too small for second derivatives of sums of squares.
+fn hessian<F: Fn(&[f64]) -> f64>(f: F, x: &[f64]) -> Vec<Vec<f64>> {
+    let n = x.len();
+    let h: Vec<f64> = x.iter().map(|v| 1.0e-4 * v.abs().max(1.0)).collect();
+    let mut xt = x.to_vec();
+    let mut eval = |i: usize, si: f64, j: usize, sj: f64| {
+        xt[i] += si * h[i];
+        xt[j] += sj * h[j];
+        let fx = f(&xt);
+        xt[i] = x[i];
+        xt[j] = x[j];
+        fx
+    };
+
+    let mut out = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let t = (eval(i, 1.0, j, 1.0) - eval(i, 1.0, j, -1.0) - eval(i, -1.0, j, 1.0)
+                + eval(i, -1.0, j, -1.0))
+                / (4.0 * h[i] * h[j]);
+            out[i][j] = t;
+            out[j][i] = t;
+        }
+    }
+    out
+}
+
+/// Objective and gradient of `f`, with the gradient calculated by forward differences.
+fn with_forward_diff<F: Fn(&Vec<f64>) -> f64>(f: F) -> impl Fn(&[f64]) -> (f64, Vec<f64>) {
+    move |coef: &[f64]| {
+        let coef = coef.to_vec();
+        (f(&coef), coef.forward_diff(&f))
+    }
+}
+
+/// Minimize an objective with the L-BFGS algorithm, starting at and updating `coef`.
+/// `fg` returns the value of the objective and its gradient.
+fn minimize<F: Fn(&[f64]) -> (f64, Vec<f64>)>(
+    coef: &mut [f64],
+    fg: F,
+    initial_step: f64,
+    options: &FitOptions,
+) -> Convergence {
+    let report = |status: ConvergenceStatus, iterations: usize, coef: &[f64]| {
+        let (objective, gradient) = fg(coef);
+        Convergence {
+            status,
+            iterations,
+            objective,
+            gradient_norm: gradient.iter().map(|g| g * g).sum::<f64>().sqrt(),
+        }
+    };
+    if coef.is_empty() {
+        return report(ConvergenceStatus::Converged, 0, coef);
+    }
+    // liblbfgs treats zero iterations as unlimited
+    if options.max_iterations == 0 {
+        return report(ConvergenceStatus::MaxIterations, 0, coef);
     }
 
     let evaluate = |x: &[f64], gx: &mut [f64]| {
-        let x_vec = x.to_vec();
-        let fx = f(&x_vec);
-        let gx_eval = g(&x_vec);
+        let (fx, gx_eval) = fg(x);
         // copy values from gx_eval into gx
         gx[..gx_eval.len()].copy_from_slice(&gx_eval[..]);
         Ok(fx)
     };
 
-    let fmin = lbfgs().with_max_iterations(200);
-    if let Err(e) = fmin.minimize(
-        &mut coef, // input variables
-        evaluate,  // define how to evaluate function
-        |_prgr| {
-            false // returning true will cancel optimization
+    // The progress is reported before each iteration, where the first iteration of liblbfgs
+    // only evaluates the starting point. liblbfgs ignores the test on the objective, so we
+    // cancel the optimization here if the relative decrease is below the function tolerance.
+    let mut niter = None;
+    let mut previous = f64::INFINITY;
+    let mut stalled = false;
+    let fmin = lbfgs()
+        .with_max_iterations(options.max_iterations + 1)
+        .with_epsilon(options.gradient_tolerance)
+        .with_initial_step_size(initial_step);
+    let result = fmin.minimize(
+        coef,     // input variables
+        evaluate, // define how to evaluate function
+        |prgr| {
+            niter = Some(prgr.niter);
+            stalled = options.function_tolerance > 0.0
+                && prgr.niter > 1
+                && previous - prgr.fx <= options.function_tolerance * prgr.fx.abs();
+            previous = prgr.fx;
+            stalled // returning true will cancel optimization
         },
-    ) {
-        tracing::warn!("Got error during fit: {}", e);
+    );
+    // number of completed steps
+    let (status, iterations) = match (result, niter) {
+        (Err(e), _) => {
+            tracing::warn!("Got error during fit: {}", e);
+            let iterations = niter.unwrap_or(1).saturating_sub(1);
+            (ConvergenceStatus::LineSearchFailed, iterations)
+        }
+        (Ok(_), None) => (ConvergenceStatus::Converged, 0),
+        (Ok(_), Some(k)) if stalled => (ConvergenceStatus::Converged, k.saturating_sub(1)),
+        (Ok(r), Some(k)) if r.gnorm / r.xnorm.max(1.0) <= options.gradient_tolerance => {
+            (ConvergenceStatus::Converged, k)
+        }
+        (Ok(_), Some(k)) => (ConvergenceStatus::MaxIterations, k),
+    };
+    report(status, iterations, coef)
+}
+
+/// Map unconstrained parameters to stationary AR and invertible MA coefficients for the
+/// AR polynomials `ar` and the MA polynomials `ma`, leaving all other parameters unchanged.
+fn constrain(
+    par: &[f64],
+    ar: &[std::ops::Range<usize>],
+    ma: &[std::ops::Range<usize>],
+) -> Vec<f64> {
+    let mut coef = par.to_vec();
+    for range in ar {
+        let phi = ar_transform(&par[range.clone()]);
+        coef[range.clone()].copy_from_slice(&phi);
+    }
+    for range in ma {
+        let theta = ma_transform(&par[range.clone()]);
+        coef[range.clone()].copy_from_slice(&theta);
+    }
+    coef
+}
+
+/// Inverse of `constrain`, fails if an AR polynomial in `ar` is not stationary or an MA
+/// polynomial in `ma` is not invertible.
+fn unconstrain(
+    coef: &[f64],
+    ar: &[std::ops::Range<usize>],
+    ma: &[std::ops::Range<usize>],
+) -> Result<Vec<f64>> {
+    let mut par = coef.to_vec();
+    for range in ar {
+        let partial = ar_inv_transform(&coef[range.clone()])?;
+        par[range.clone()].copy_from_slice(&partial);
+    }
+    for range in ma {
+        let partial = ma_inv_transform(&coef[range.clone()])?;
+        par[range.clone()].copy_from_slice(&partial);
+    }
+    for range in ar.iter().chain(ma) {
+        for c in par[range.clone()].iter_mut() {
+            *c = c.atanh();
+        }
+    }
+    Ok(par)
+}
+
+/// Map unconstrained parameters to the coefficients of a stationary AR polynomial
+/// (Jones, 1980). The parameters are transformed into partial autocorrelations
+/// in (-1, 1) with `tanh`, which are then turned into AR coefficients with the
+/// Durbin-Levinson recursion.
+fn ar_transform(raw: &[f64]) -> Vec<f64> {
+    let mut phi: Vec<f64> = raw.iter().map(|r| r.tanh()).collect();
+    let mut work = phi.clone();
+    for j in 1..phi.len() {
+        let a = phi[j];
+        for k in 0..j {
+            work[k] -= a * phi[j - k - 1];
+        }
+        phi[..j].copy_from_slice(&work[..j]);
+    }
+    phi
+}
+
+/// Inverse of `ar_transform` up to the final `atanh`, i.e. returns the partial
+/// autocorrelations of a stationary AR polynomial.
+fn ar_inv_transform(phi: &[f64]) -> Result<Vec<f64>> {
+    let mut partial = phi.to_vec();
+    let mut work = partial.clone();
+    for j in (1..partial.len()).rev() {
+        let a = partial[j];
+        if a.abs() >= 1.0 {
+            anyhow::bail!("AR part is not stationary");
+        }
+        for k in 0..j {
+            work[k] = (partial[k] + a * partial[j - k - 1]) / (1.0 - a * a);
+        }
+        partial[..j].copy_from_slice(&work[..j]);
+    }
+    if partial.iter().any(|a| a.abs() >= 1.0) {
+        anyhow::bail!("AR part is not stationary");
+    }
+    Ok(partial)
+}
+
+/// Map unconstrained parameters to the coefficients of an invertible MA polynomial
+/// (Monahan, 1984). The MA polynomial `1 + theta_1 z + ...` is invertible exactly if
+/// `-theta` are the coefficients of a stationary AR polynomial.
+fn ma_transform(raw: &[f64]) -> Vec<f64> {
+    ar_transform(raw).iter().map(|a| -a).collect()
+}
+
+/// Replace the roots of the MA polynomial `1 + theta_1 z + ...` inside the unit circle by
+/// their reciprocals. The resulting polynomial is invertible, unless it has roots on the
+/// unit circle, and has the same spectral shape.
+fn ma_invert(theta: &[f64]) -> Vec<f64> {
+    // multiply out the factors (1 - z / r) for all roots r
+    let mut coef = vec![Complex::new(1.0, 0.0)];
+    for root in poly::ma_roots(theta) {
+        let root = if root.norm() < 1.0 { 1.0 / root } else { root };
+        let mut next = coef.clone();
+        next.push(Complex::new(0.0, 0.0));
+        for k in 1..next.len() {
+            next[k] -= coef[k - 1] / root;
+        }
+        coef = next;
+    }
+    // roots of trailing zero coefficients are not counted
+    let mut inverted: Vec<f64> = coef[1..].iter().map(|c| c.re).collect();
+    inverted.resize(theta.len(), 0.0);
+    inverted
+}
+
+/// Inverse of `ma_transform` up to the final `atanh`.
+fn ma_inv_transform(theta: &[f64]) -> Result<Vec<f64>> {
+    let negated: Vec<f64> = theta.iter().map(|a| -a).collect();
+    ar_inv_transform(&negated).context("MA part is not invertible")
+}
+
+/// Information criterion used to rank candidate models in `autofit_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InformationCriterion {
+    /// Akaike information criterion.
+    Aic,
+    /// Akaike information criterion with small sample correction.
+    #[default]
+    Aicc,
+    /// Bayesian information criterion.
+    Bic,
+}
+
+impl InformationCriterion {
+    fn evaluate(&self, model: &FittedArima) -> f64 {
+        match self {
+            InformationCriterion::Aic => model.aic(),
+            InformationCriterion::Aicc => model.aicc(),
+            InformationCriterion::Bic => model.bic(),
+        }
     }
+}
+
+/// Options for the order selection in `autofit_with_options`.
+///
+/// # Example
+///
+/// ```
+/// use arima::estimate::{AutofitOptions, InformationCriterion};
+/// let options = AutofitOptions::new()
+///     .with_d(1)
+///     .with_max_order(3, 3)
+///     .with_criterion(InformationCriterion::Bic)
+///     .with_stepwise(false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutofitOptions {
+    d: Option<usize>,
+    max_p: usize,
+    max_q: usize,
+    max_seasonal_p: usize,
+    max_seasonal_q: usize,
+    max_total: usize,
+    criterion: InformationCriterion,
+    stepwise: bool,
+    fit_options: FitOptions,
+}
 
-    Ok(coef)
+impl Default for AutofitOptions {
+    fn default() -> AutofitOptions {
+        AutofitOptions {
+            d: None,
+            max_p: 5,
+            max_q: 5,
+            max_seasonal_p: 2,
+            max_seasonal_q: 2,
+            max_total: 5,
+            criterion: InformationCriterion::default(),
+            stepwise: true,
+            fit_options: FitOptions::new().with_method(Method::CssMl),
+        }
+    }
 }
 
-/// TODO clean up
-/// Auto-fit an ARIMA model, guessing AR and MA orders.
-/// See `fit` for more details.
+impl AutofitOptions {
+    /// Create options with the defaults used by `autofit`.
+    pub fn new() -> AutofitOptions {
+        Default::default()
+    }
+
+    /// Set the order of differencing. If not set, it is chosen with `unitroot::ndiffs`
+    /// using the KPSS test at the 5% level and at most two differences, or set to zero if
+    /// the series is too short for the test.
+    pub fn with_d(mut self, d: usize) -> AutofitOptions {
+        self.d = Some(d);
+        self
+    }
+
+    /// Set the maximum AR and MA orders. Default to 5.
+    pub fn with_max_order(mut self, max_p: usize, max_q: usize) -> AutofitOptions {
+        self.max_p = max_p;
+        self.max_q = max_q;
+        self
+    }
+
+    /// Set the maximum seasonal AR and MA orders. Default to 2. Only used if the model
+    /// has a seasonal part, see `with_fit_options`.
+    pub fn with_max_seasonal_order(mut self, max_p: usize, max_q: usize) -> AutofitOptions {
+        self.max_seasonal_p = max_p;
+        self.max_seasonal_q = max_q;
+        self
+    }
+
+    /// Set the maximum sum `p + q + P + Q` of all orders. Defaults to 5.
+    pub fn with_max_total_order(mut self, max_total: usize) -> AutofitOptions {
+        self.max_total = max_total;
+        self
+    }
+
+    /// Set the information criterion used to compare models. Defaults to AICc.
+    pub fn with_criterion(mut self, criterion: InformationCriterion) -> AutofitOptions {
+        self.criterion = criterion;
+        self
+    }
+
+    /// Use the stepwise search of Hyndman and Khandakar (2008) if `true` (the default),
+    /// otherwise fit all models up to the maximum orders.
+    pub fn with_stepwise(mut self, stepwise: bool) -> AutofitOptions {
+        self.stepwise = stepwise;
+        self
+    }
+
+    /// Set the options used to fit the candidate models. Defaults to `Method::CssMl`.
+    /// A seasonal part set with `FitOptions::with_seasonal` fixes the seasonal differencing
+    /// and period, while the seasonal AR and MA orders are searched.
+    pub fn with_fit_options(mut self, fit_options: FitOptions) -> AutofitOptions {
+        self.fit_options = fit_options;
+        self
+    }
+}
+
+/// A model considered during the order selection, with the value of the information
+/// criterion. The value is infinite if the model could not be fitted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    /// Order (p, d, q).
+    pub order: (usize, usize, usize),
+    /// Seasonal order (P, D, Q) and period.
+    pub seasonal: SeasonalOrder,
+    /// Value of the information criterion.
+    pub ic: f64,
+}
+
+/// Result of `autofit_with_options`: the selected model and all candidates that were fitted.
+#[derive(Debug, Clone)]
+pub struct OrderSelection {
+    /// Model with the smallest information criterion.
+    pub model: FittedArima,
+    /// All fitted candidates in the order they were considered.
+    pub candidates: Vec<Candidate>,
+}
+
+impl OrderSelection {
+    /// Order (p, d, q) of the selected model.
+    pub fn order(&self) -> (usize, usize, usize) {
+        self.model.order()
+    }
+
+    /// Seasonal order of the selected model.
+    pub fn seasonal_order(&self) -> SeasonalOrder {
+        self.model.seasonal_order()
+    }
+}
+
+/// Auto-fit an ARIMA model, selecting the AR and MA orders by the AICc.
+/// See `autofit_with_options` for more details.
 ///
 /// # Arguments
 ///
 /// * `&x` - Vector of the timeseries.
-/// * `d` - Order of differencing.
+/// * `d` - Order of differencing. Chosen by `unitroot::ndiffs` if `None`.
 ///
 /// # Returns
 ///
-/// * ARIMA coefficients minimizing the conditional sum of squares (CSS).
+/// * Fitted model with the selected orders.
 pub fn autofit<
     T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign + Div + Debug,
 >(
     x: &[T],
-    d: usize,
-) -> Result<Vec<f64>> {
+    d: Option<usize>,
+) -> Result<FittedArima> {
+    let options = AutofitOptions {
+        d,
+        ..Default::default()
+    };
+    Ok(autofit_with_options(x, &options)?.model)
+}
+
+/// Select the orders of an ARIMA model by an information criterion.
+///
+/// The stepwise search of Hyndman and Khandakar (2008) starts with the models
+/// ARIMA(2,d,2), ARIMA(0,d,0), ARIMA(1,d,0), ARIMA(0,d,1) and the orders suggested by the
+/// significant lags of the sample ACF and PACF. It then repeatedly moves to the best model
+/// that differs from the current one by one in p, q, P or Q, or by one in both p and q
+/// or P and Q, until no neighbor improves the criterion. Alternatively, all models up to
+/// the maximum orders can be fitted.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `&options` - Order selection options.
+///
+/// # Returns
+///
+/// * The selected model and the table of all fitted candidates.
+///
+/// # Example
+///
+/// ```
+/// use arima::estimate::{self, AutofitOptions};
+/// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2, 1.0, 1.3, 1.5, 1.2];
+/// let options = AutofitOptions::new().with_max_order(2, 2);
+/// let selection = estimate::autofit_with_options(&x, &options).unwrap();
+/// let best = selection
+///     .candidates
+///     .iter()
+///     .fold(f64::INFINITY, |m, c| m.min(c.ic));
+/// assert_eq!(selection.model.aicc(), best);
+/// ```
+pub fn autofit_with_options<
+    T: Float + From<u32> + From<f64> + Into<f64> + Copy + Add + AddAssign + Div + Debug,
+>(
+    x: &[T],
+    options: &AutofitOptions,
+) -> Result<OrderSelection> {
     let x: Vec<f64> = x.iter().map(|v| (*v).into()).collect();
-    let n = x.len() as f64;
-    let n_lags = 12;
+    let base = options.fit_options.seasonal;
+    let d = match options.d {
+        Some(d) => d,
+        None => {
+            let w = util::diff_seasonal(&x, base.d, base.period);
+            unitroot::ndiffs(&w, UnitRootTest::Kpss, 0.05, 2).unwrap_or(0)
+        }
+    };
+    let seasonal = base.period > 0;
+    let (max_sp, max_sq) = if seasonal {
+        (options.max_seasonal_p, options.max_seasonal_q)
+    } else {
+        (0, 0)
+    };
 
-    // Hardcoding for now
-    // let alpha = 0.05;
-    // ppf = scipy.stats.norm.ppf(1 - alpha / 2.0)
-    let ppf = 1.959963984540054;
+    // orders (p, q, P, Q) within the limits
+    let valid = |o: &[usize; 4]| {
+        o[0] <= options.max_p
+            && o[1] <= options.max_q
+            && o[2] <= max_sp
+            && o[3] <= max_sq
+            && o.iter().sum::<usize>() <= options.max_total
+    };
 
-    // Estimate MA order
-    // <https://www.statsmodels.org/devel/_modules/statsmodels/tsa/stattools.html#acf>
-    let _acf = acf::acf(&x, Some(n_lags), false).unwrap();
-    let mult: Vec<f64> = _acf[1.._acf.len() - 1]
-        .iter()
-        .scan(0., |acc, v| {
-            *acc += v.powf(2.);
-            Some(1. + 2. * *acc)
-        })
-        .collect();
-    let mut varacf = vec![0., 1. / n];
-    let varacf_end: Vec<f64> = (0.._acf.len() - 2).map(|i| 1. / n * mult[i]).collect();
-    varacf.extend(varacf_end);
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut best: Option<(f64, [usize; 4], FittedArima)> = None;
+    let mut fit_order = |o: [usize; 4], best: &mut Option<(f64, [usize; 4], FittedArima)>| {
+        let seasonal_order = SeasonalOrder {
+            p: o[2],
+            q: o[3],
+            ..base
+        };
+        if candidates
+            .iter()
+            .any(|c| c.order == (o[0], d, o[1]) && c.seasonal == seasonal_order)
+        {
+            return false;
+        }
+        let fit_options = FitOptions {
+            seasonal: seasonal_order,
+            init: None,
+            fixed: Vec::new(),
+            ..options.fit_options.clone()
+        };
+        let (ic, model) = match fit_with_options(&x, o[0], d, o[1], &fit_options) {
+            Ok(model) => (options.criterion.evaluate(&model), Some(model)),
+            Err(_) => (f64::INFINITY, None),
+        };
+        let ic = if ic.is_nan() { f64::INFINITY } else { ic };
+        candidates.push(Candidate {
+            order: (o[0], d, o[1]),
+            seasonal: seasonal_order,
+            ic,
+        });
+        match (model, &best) {
+            (Some(model), Some((best_ic, _, _))) if ic < *best_ic => {
+                *best = Some((ic, o, model));
+                true
+            }
+            (Some(model), None) if ic < f64::INFINITY => {
+                *best = Some((ic, o, model));
+                true
+            }
+            _ => false,
+        }
+    };
 
-    let interval: Vec<f64> = varacf.iter().map(|v| ppf * v.sqrt()).collect();
-    let confint: Vec<(f64, f64)> = _acf
-        .iter()
-        .zip(&interval)
-        .map(|(p, q)| (p - q, p + q))
-        .collect();
-    let bounds: Vec<(f64, f64)> = confint
-        .iter()
-        .zip(&_acf)
-        .map(|((l, u), a)| (l - a, u - a))
-        .collect();
+    if options.stepwise {
+        let w = util::diff(&x, d);
+        let w = util::diff_seasonal(&w, base.d, base.period);
+        let (ar_order, ma_order) = acf_pacf_orders(&w)?;
+        let (sp, sq) = (min(1, max_sp), min(1, max_sq));
+        let start = [
+            [2, 2, sp, sq],
+            [0, 0, 0, 0],
+            [1, 0, sp, 0],
+            [0, 1, 0, sq],
+            [ar_order, ma_order, 0, 0],
+        ];
+        for o in start {
+            if valid(&o) {
+                fit_order(o, &mut best);
+            }
+        }
+
+        while let Some((_, current, _)) = &best {
+            let current = *current;
+            let mut improved = false;
+            let steps: [[isize; 4]; 16] = [
+                [-1, 0, 0, 0],
+                [1, 0, 0, 0],
+                [0, -1, 0, 0],
+                [0, 1, 0, 0],
+                [-1, -1, 0, 0],
+                [1, 1, 0, 0],
+                [-1, 1, 0, 0],
+                [1, -1, 0, 0],
+                [0, 0, -1, 0],
+                [0, 0, 1, 0],
+                [0, 0, 0, -1],
+                [0, 0, 0, 1],
+                [0, 0, -1, -1],
+                [0, 0, 1, 1],
+                [0, 0, -1, 1],
+                [0, 0, 1, -1],
+            ];
+            // fit all neighbors, `best` ends up at the one with the smallest criterion
+            for step in steps {
+                let mut o = current;
+                let mut in_range = true;
+                for i in 0..4 {
+                    match current[i].checked_add_signed(step[i]) {
+                        Some(v) => o[i] = v,
+                        None => in_range = false,
+                    }
+                }
+                if in_range && valid(&o) && fit_order(o, &mut best) {
+                    improved = true;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+    } else {
+        for p in 0..=options.max_p {
+            for q in 0..=options.max_q {
+                for sp in 0..=max_sp {
+                    for sq in 0..=max_sq {
+                        let o = [p, q, sp, sq];
+                        if valid(&o) {
+                            fit_order(o, &mut best);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    match best {
+        Some((_, _, model)) => Ok(OrderSelection { model, candidates }),
+        None => anyhow::bail!("None of the candidate models could be fitted"),
+    }
+}
+
+/// Guess the AR and MA orders from the number of leading lags of the sample PACF
+/// and ACF that are significantly different from zero.
+fn acf_pacf_orders(x: &[f64]) -> Result<(usize, usize)> {
+    if x.len() < 4 {
+        return Ok((0, 0));
+    }
+    let n_lags = min(12, x.len() / 2);
+    let alpha = 0.05;
+
+    // Estimate MA order from the ACF with Bartlett's intervals
+    // <https://www.statsmodels.org/devel/_modules/statsmodels/tsa/stattools.html#acf>
+    let (_acf, confint) = acf::acf_with_confint(x, Some(n_lags), alpha)?;
 
     // Subtract one to compensate for the first value (lag=0)
     let ma_order = _acf
         .iter()
-        .zip(bounds)
-        .take_while(|(a, (l, u))| a < &l || a > &u)
+        .zip(confint)
+        .take_while(|(a, (_l, u))| a.abs() > u - *a)
         .count()
         - 1;
 
     // <https://www.statsmodels.org/devel/_modules/statsmodels/tsa/stattools.html#pacf>
-    let _pacf = acf::pacf(&x, Some(n_lags)).unwrap();
-    let pacf_varacf = 1.0 / n;
-    let pacf_interval = ppf * pacf_varacf.sqrt();
-    let pacf_confint: Vec<(f64, f64)> = _pacf
-        .iter()
-        .map(|p| (p - pacf_interval, p + pacf_interval))
-        .collect();
-
-    let pacf_bounds: Vec<(f64, f64)> = pacf_confint
-        .iter()
-        .zip(&_pacf)
-        .map(|((l, u), a)| (l - a, u - a))
-        .collect();
+    let (_pacf, pacf_confint) = acf::pacf_with_confint(x, Some(n_lags), alpha)?;
 
     // lag=0 isn't included so no need to subtract one
     let ar_order = _pacf
         .iter()
-        .zip(pacf_bounds)
-        .take_while(|(a, (l, u))| a < &l || a > &u)
+        .zip(pacf_confint)
+        .take_while(|(a, (_l, u))| a.abs() > u - *a)
         .count();
 
-    fit(&x, ar_order, d, ma_order)
+    Ok((ar_order, ma_order))
 }
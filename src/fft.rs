@@ -0,0 +1,80 @@
+use num::complex::Complex;
+
+/// In-place radix-2 fast Fourier transform (Cooley-Tukey). The length of `buf` has to be
+/// a power of two. The inverse transform is not scaled by 1/n.
+pub(crate) fn fft(buf: &mut [Complex<f64>], inverse: bool) {
+    let n = buf.len();
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+    if n < 2 {
+        return;
+    }
+
+    // bit reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+
+    // twiddle factors exp(-+2 pi i k / n), computed directly for accuracy
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let twiddles: Vec<Complex<f64>> = (0..n / 2)
+        .map(|k| Complex::from_polar(1.0, sign * 2.0 * std::f64::consts::PI * k as f64 / n as f64))
+        .collect();
+
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for chunk in buf.chunks_mut(len) {
+            let (lower, upper) = chunk.split_at_mut(len / 2);
+            for (k, (a, b)) in lower.iter_mut().zip(upper.iter_mut()).enumerate() {
+                let t = *b * twiddles[k * stride];
+                *b = *a - t;
+                *a += t;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Discrete Fourier transform `X_k = sum_t x_t exp(-2 pi i k t / n)` of any length, using
+/// Bluestein's algorithm with power-of-two FFTs if the length is not a power of two.
+pub(crate) fn dft(x: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let n = x.len();
+    if n.is_power_of_two() {
+        let mut buf = x.to_vec();
+        fft(&mut buf, false);
+        return buf;
+    }
+
+    // chirp exp(-pi i k^2 / n), with k^2 reduced modulo 2n for accuracy
+    let chirp: Vec<Complex<f64>> = (0..n)
+        .map(|k| {
+            let k2 = (k * k) % (2 * n);
+            Complex::from_polar(1.0, -std::f64::consts::PI * k2 as f64 / n as f64)
+        })
+        .collect();
+
+    // X_k = chirp_k * sum_t (x_t chirp_t) conj(chirp_k-t) is a convolution
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex::new(0.0, 0.0); m];
+    for (ai, (xi, c)) in a.iter_mut().zip(x.iter().zip(&chirp)) {
+        *ai = xi * c;
+    }
+    let mut b = vec![Complex::new(0.0, 0.0); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+    fft(&mut a, false);
+    fft(&mut b, false);
+    for (ai, bi) in a.iter_mut().zip(&b) {
+        *ai *= bi / m as f64;
+    }
+    fft(&mut a, true);
+
+    a.iter().zip(&chirp).map(|(ai, c)| ai * c).collect()
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+
+use std::f64::consts::PI;
+
+/// Exact Gaussian likelihood of an ARMA model, as computed by `arma_loglik`.
+/// The likelihood is concentrated with respect to the innovation variance.
+#[derive(Debug, Clone)]
+pub struct ArmaLikelihood {
+    /// Sum of the squared innovations divided by their relative variance, `sum(v_t^2/F_t)`.
+    pub ssq: f64,
+    /// Sum of the logarithms of the relative innovation variances, `sum(log(F_t))`.
+    pub sumlog: f64,
+    /// Number of observations.
+    pub nobs: usize,
+    /// Standardized innovations `v_t/sqrt(F_t)`.
+    pub residuals: Vec<f64>,
+}
+
+impl ArmaLikelihood {
+    /// Maximum likelihood estimate of the innovation variance.
+    pub fn sigma2(&self) -> f64 {
+        self.ssq / self.nobs as f64
+    }
+
+    /// Gaussian log-likelihood evaluated at the estimated innovation variance.
+    pub fn loglik(&self) -> f64 {
+        let n = self.nobs as f64;
+        -0.5 * (n * ((2.0 * PI * self.sigma2()).ln() + 1.0) + self.sumlog)
+    }
+
+    /// Negative concentrated log-likelihood divided by the number of observations,
+    /// up to a constant. This is the objective minimized in maximum likelihood estimation.
+    pub fn objective(&self) -> f64 {
+        0.5 * (self.sigma2().ln() + self.sumlog / self.nobs as f64)
+    }
+}
+
+/// Calculate the exact Gaussian likelihood of a zero-mean ARMA process with a Kalman filter.
+/// The ARMA(p, q) model is written in the state space form of Harvey (1989) and Gardner et al.
+/// (1980) with a state vector of dimension `r = max(p, q+1)`. The filter is initialized
+/// with the stationary state covariance, so the AR part has to be stationary.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the zero-mean timeseries. Any differencing and centering should be
+///   done before.
+/// * `&phi` - AR parameter vector.
+/// * `&theta` - MA parameter vector.
+///
+/// # Returns
+///
+/// * The concentrated likelihood and the standardized innovations.
+///
+/// # Example
+///
+/// ```
+/// use arima::kalman;
+/// let x = [0.5, -0.2, 0.3, 0.8, -0.4];
+/// let lik = kalman::arma_loglik(&x, &[0.5], &[]).unwrap();
+///
+/// // exact likelihood of an AR(1) model
+/// let ssq = x[0] * x[0] * (1.0 - 0.25)
+///     + (1..5).map(|t| (x[t] - 0.5 * x[t - 1]).powi(2)).sum::<f64>();
+/// assert!((lik.ssq - ssq).abs() < 1.0e-10);
+/// assert!((lik.sumlog - (1.0f64 / 0.75).ln()).abs() < 1.0e-10);
+/// ```
+pub fn arma_loglik(x: &[f64], phi: &[f64], theta: &[f64]) -> Result<ArmaLikelihood> {
+    let p = phi.len();
+    let q = theta.len();
+    let r = std::cmp::max(p, q + 1);
+
+    // R = (1, theta_1, ..., theta_r-1)
+    let mut rv = vec![0.0; r];
+    rv[0] = 1.0;
+    rv[1..q + 1].copy_from_slice(theta);
+
+    let phi_r = |i: usize| if i < p { phi[i] } else { 0.0 };
+
+    let mut a = vec![0.0; r];
+    let mut pm = stationary_covariance(phi, &rv)?;
+
+    let mut ssq = 0.0;
+    let mut sumlog = 0.0;
+    let mut residuals = Vec::with_capacity(x.len());
+    let mut steady = false;
+
+    for (t, xt) in x.iter().enumerate() {
+        // innovation and its relative variance
+        let v = xt - a[0];
+        let f = pm[0];
+        if f <= 0.0 || !f.is_finite() {
+            anyhow::bail!("Innovation variance is not positive");
+        }
+        ssq += v * v / f;
+        sumlog += f.ln();
+        residuals.push(v / f.sqrt());
+
+        // update: a = a + P[.,0] v/F
+        let anew: Vec<f64> = (0..r).map(|i| a[i] + pm[i * r] * v / f).collect();
+
+        // predict: a = T a
+        for i in 0..r {
+            let next = if i + 1 < r { anew[i + 1] } else { 0.0 };
+            a[i] = phi_r(i) * anew[0] + next;
+        }
+
+        // once the filter has converged, the state covariance does not change anymore
+        if steady {
+            continue;
+        }
+        if t > r && (f - 1.0).abs() < 1.0e-12 {
+            steady = true;
+            continue;
+        }
+
+        // update: P = P - P[.,0] P[0,.] / F
+        let mut pu = pm.clone();
+        for i in 0..r {
+            for j in 0..r {
+                pu[i * r + j] -= pm[i * r] * pm[j] / f;
+            }
+        }
+
+        // predict: P = T P T' + R R'
+        let mut tp = vec![0.0; r * r];
+        for i in 0..r {
+            for j in 0..r {
+                let next = if i + 1 < r { pu[(i + 1) * r + j] } else { 0.0 };
+                tp[i * r + j] = phi_r(i) * pu[j] + next;
+            }
+        }
+        for i in 0..r {
+            for j in 0..r {
+                let next = if j + 1 < r { tp[i * r + j + 1] } else { 0.0 };
+                pm[i * r + j] = tp[i * r] * phi_r(j) + next + rv[i] * rv[j];
+            }
+        }
+    }
+
+    Ok(ArmaLikelihood {
+        ssq,
+        sumlog,
+        nobs: x.len(),
+        residuals,
+    })
+}
+
+/// Solve the Lyapunov equation `P = T P T' + R R'` for the stationary covariance of the
+/// state vector with the doubling algorithm. The result is stored row-major.
+fn stationary_covariance(phi: &[f64], rv: &[f64]) -> Result<Vec<f64>> {
+    let r = rv.len();
+
+    // transition matrix with phi in the first column and ones on the superdiagonal
+    let mut tm = vec![0.0; r * r];
+    for i in 0..r {
+        if i < phi.len() {
+            tm[i * r] = phi[i];
+        }
+        if i + 1 < r {
+            tm[i * r + i + 1] = 1.0;
+        }
+    }
+
+    let mut pm = vec![0.0; r * r];
+    for i in 0..r {
+        for j in 0..r {
+            pm[i * r + j] = rv[i] * rv[j];
+        }
+    }
+
+    // P_k+1 = P_k + A_k P_k A_k', A_k+1 = A_k A_k
+    for _ in 0..64 {
+        let apa = matmul(&matmul(&tm, &pm, r), &transpose(&tm, r), r);
+        for i in 0..r * r {
+            pm[i] += apa[i];
+        }
+        tm = matmul(&tm, &tm, r);
+
+        let norm = tm.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+        if !norm.is_finite() || pm.iter().any(|v| !v.is_finite()) {
+            break;
+        }
+        if norm < 1.0e-14 {
+            return Ok(pm);
+        }
+    }
+    anyhow::bail!("Non-stationary AR part")
+}
+
+fn matmul(a: &[f64], b: &[f64], r: usize) -> Vec<f64> {
+    let mut c = vec![0.0; r * r];
+    for i in 0..r {
+        for k in 0..r {
+            let aik = a[i * r + k];
+            if aik == 0.0 {
+                continue;
+            }
+            for j in 0..r {
+                c[i * r + j] += aik * b[k * r + j];
+            }
+        }
+    }
+    c
+}
+
+fn transpose(a: &[f64], r: usize) -> Vec<f64> {
+    let mut t = vec![0.0; r * r];
+    for i in 0..r {
+        for j in 0..r {
+            t[j * r + i] = a[i * r + j];
+        }
+    }
+    t
+}
@@ -1,8 +1,19 @@
 pub mod acf;
+pub mod arma;
+pub mod diagnostics;
 pub mod sim;
+pub mod spectrum;
+pub mod stats;
 pub mod util;
 
 pub mod estimate;
+pub mod kalman;
+pub mod model;
+pub mod poly;
+pub mod unitroot;
+
+mod fft;
+mod linalg;
 
 #[cfg(feature = "accelerate")]
 extern crate accelerate_src as raw;
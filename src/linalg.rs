@@ -0,0 +1,113 @@
+use anyhow::Result;
+
+/// Solve the linear system `a * x = b` for a square matrix `a`, given as a vector of rows.
+/// Uses Gaussian elimination with partial pivoting.
+pub(crate) fn solve(a: &[Vec<f64>], b: &[f64]) -> Result<Vec<f64>> {
+    let n = b.len();
+    let mut m: Vec<Vec<f64>> = a.to_vec();
+    let mut x: Vec<f64> = b.to_vec();
+
+    for col in 0..n {
+        // find the pivot row
+        let pivot = (col..n)
+            .max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs()))
+            .unwrap();
+        if m[pivot][col].abs() < 1.0e-300 {
+            anyhow::bail!("Matrix is singular");
+        }
+        m.swap(col, pivot);
+        x.swap(col, pivot);
+
+        let (upper, lower) = m.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (i, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (r, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *r -= factor * p;
+            }
+            x[col + 1 + i] -= factor * x[col];
+        }
+    }
+
+    // back substitution
+    for col in (0..n).rev() {
+        let sum: f64 = m[col][col + 1..]
+            .iter()
+            .zip(&x[col + 1..])
+            .map(|(a, b)| a * b)
+            .sum();
+        x[col] = (x[col] - sum) / m[col][col];
+    }
+    Ok(x)
+}
+
+/// Ordinary least squares fit of `y` on the given regressors, passed as columns of equal
+/// length. Solves the normal equations and returns one coefficient per column.
+pub(crate) fn lstsq(columns: &[Vec<f64>], y: &[f64]) -> Result<Vec<f64>> {
+    let k = columns.len();
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    for i in 0..k {
+        for j in i..k {
+            let s: f64 = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+            xtx[i][j] = s;
+            xtx[j][i] = s;
+        }
+        xty[i] = columns[i].iter().zip(y).map(|(a, b)| a * b).sum();
+    }
+    solve(&xtx, &xty)
+}
+
+/// Inverse of a square matrix, given as a vector of rows.
+pub(crate) fn inverse(a: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut columns = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut e = vec![0.0; n];
+        e[i] = 1.0;
+        columns.push(solve(a, &e)?);
+    }
+    // the solutions are the columns of the inverse
+    Ok((0..n)
+        .map(|i| columns.iter().map(|c| c[i]).collect())
+        .collect())
+}
+
+/// Result of an ordinary least squares regression with `ols`.
+pub(crate) struct Ols {
+    pub coef: Vec<f64>,
+    pub std_errors: Vec<f64>,
+    pub ssr: f64,
+}
+
+/// Ordinary least squares fit of `y` on the given regressors, passed as columns of equal
+/// length, with the standard errors of the coefficients and the sum of squared residuals.
+pub(crate) fn ols(columns: &[Vec<f64>], y: &[f64]) -> Result<Ols> {
+    let k = columns.len();
+    let mut xtx = vec![vec![0.0; k]; k];
+    for i in 0..k {
+        for j in i..k {
+            let s: f64 = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+            xtx[i][j] = s;
+            xtx[j][i] = s;
+        }
+    }
+    let coef = lstsq(columns, y)?;
+    let ssr: f64 = (0..y.len())
+        .map(|t| {
+            let fitted: f64 = columns.iter().zip(&coef).map(|(c, b)| c[t] * b).sum();
+            (y[t] - fitted).powi(2)
+        })
+        .sum();
+    let sigma2 = ssr / (y.len() - k) as f64;
+    let inv = inverse(&xtx)?;
+    let std_errors = (0..k).map(|i| (sigma2 * inv[i][i]).sqrt()).collect();
+    Ok(Ols {
+        coef,
+        std_errors,
+        ssr,
+    })
+}
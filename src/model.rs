@@ -0,0 +1,746 @@
+use anyhow::Result;
+
+use num::complex::Complex;
+use std::fmt;
+
+use crate::diagnostics::{self, Diagnostics};
+use crate::{acf, arma, poly, spectrum, stats, util};
+
+/// Seasonal order (P, D, Q) and period s of a SARIMA model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SeasonalOrder {
+    /// Order of the seasonal AR coefficients.
+    pub p: usize,
+    /// Order of seasonal differencing.
+    pub d: usize,
+    /// Order of the seasonal MA coefficients.
+    pub q: usize,
+    /// Number of observations per season.
+    pub period: usize,
+}
+
+/// Outcome of the numerical optimization in `estimate::fit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvergenceStatus {
+    /// The gradient or the function tolerance was reached.
+    Converged,
+    /// The optimizer stopped at the maximum number of iterations.
+    MaxIterations,
+    /// The line search did not find a step that decreases the objective. The coefficients
+    /// are the last point before the failed step.
+    LineSearchFailed,
+}
+
+/// Convergence report of the optimizer, see `FittedArima::convergence`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Convergence {
+    /// Why the optimizer stopped.
+    pub status: ConvergenceStatus,
+    /// Number of completed L-BFGS iterations.
+    pub iterations: usize,
+    /// Final value of the objective, i.e. the conditional sum of squares for CSS and the
+    /// scaled negative log-likelihood `0.5 * (ln(sigma2) + sumlog / n)` for ML.
+    pub objective: f64,
+    /// Euclidean norm of the gradient of the objective with respect to the optimized
+    /// (possibly transformed) parameters at the final point.
+    pub gradient_norm: f64,
+}
+
+impl Convergence {
+    /// Whether the optimizer reached one of its tolerances.
+    pub fn converged(&self) -> bool {
+        self.status == ConvergenceStatus::Converged
+    }
+}
+
+/// Point forecasts with standard errors and prediction intervals, see `FittedArima::predict`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    /// Conditional mean of the next values.
+    pub mean: Vec<f64>,
+    /// Standard error of the forecast per horizon.
+    pub std_errors: Vec<f64>,
+    /// Prediction intervals, one per requested confidence level.
+    pub intervals: Vec<PredictionInterval>,
+}
+
+/// Gaussian prediction interval `mean -/+ z * std_error` at a confidence level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictionInterval {
+    /// Confidence level, e.g. 0.95.
+    pub level: f64,
+    /// Lower bounds per horizon.
+    pub lower: Vec<f64>,
+    /// Upper bounds per horizon.
+    pub upper: Vec<f64>,
+}
+
+/// A fitted ARIMA(p, d, q) or SARIMA(p, d, q)(P, D, Q)_s model as returned by `estimate::fit`.
+///
+/// The model is expressed on the differenced series `w_t`:
+/// `w_t = intercept + phi_1 * w_t-1 + ... + theta_1 * e_t-1 + ... + e_t`.
+/// The non-seasonal coefficients `phi()` and `theta()` and the seasonal coefficients
+/// `seasonal_phi()` and `seasonal_theta()` are stored separately. Methods that need the
+/// full ARMA polynomials, e.g. `forecast` or `psi_weights`, multiply them out on demand,
+/// see `util::expand_seasonal_ar` and `util::expand_seasonal_ma`.
+#[derive(Debug, Clone)]
+pub struct FittedArima {
+    order: (usize, usize, usize),
+    seasonal: SeasonalOrder,
+    intercept: f64,
+    phi: Vec<f64>,
+    theta: Vec<f64>,
+    sphi: Vec<f64>,
+    stheta: Vec<f64>,
+    beta: Vec<f64>,
+    cov: Vec<Vec<f64>>,
+    n_free: usize,
+    convergence: Convergence,
+    sigma2: f64,
+    loglik: f64,
+    nobs: usize,
+    residuals: Vec<f64>,
+    fitted: Vec<f64>,
+    x: Vec<f64>,
+    xreg: Vec<Vec<f64>>,
+}
+
+impl FittedArima {
+    /// Build a fitted model from the original (undifferenced) series, the estimated
+    /// coefficients, the regressors and the in-sample residuals of the differenced series.
+    /// The coefficients are the intercept, followed by the AR, MA, seasonal AR, seasonal MA
+    /// and regression coefficients.
+    pub(crate) fn new(
+        x: &[f64],
+        order: (usize, usize, usize),
+        seasonal: SeasonalOrder,
+        coef: &[f64],
+        xreg: &[Vec<f64>],
+        residuals: Vec<f64>,
+    ) -> FittedArima {
+        let (p, d, q) = order;
+        let intercept = coef[0];
+        let phi = &coef[1..1 + p];
+        let theta = &coef[1 + p..1 + p + q];
+        let sphi = &coef[1 + p + q..1 + p + q + seasonal.p];
+        let stheta = &coef[1 + p + q + seasonal.p..1 + p + q + seasonal.p + seasonal.q];
+        let beta = &coef[1 + p + q + seasonal.p + seasonal.q..];
+        let cov = vec![vec![f64::NAN; coef.len()]; coef.len()];
+
+        // the first p+P*s residuals are zero by construction and not counted
+        let nobs = residuals.len() - p - seasonal.p * seasonal.period;
+        let css: f64 = residuals.iter().map(|r| r * r).sum();
+        let sigma2 = css / nobs as f64;
+        let loglik = -0.5 * nobs as f64 * ((2.0 * std::f64::consts::PI * sigma2).ln() + 1.0);
+
+        // one-step-ahead predictions on the original scale
+        let fitted = x[d + seasonal.d * seasonal.period..]
+            .iter()
+            .zip(&residuals)
+            .map(|(xt, et)| xt - et)
+            .collect();
+
+        FittedArima {
+            order,
+            seasonal,
+            intercept,
+            phi: phi.to_vec(),
+            theta: theta.to_vec(),
+            sphi: sphi.to_vec(),
+            stheta: stheta.to_vec(),
+            beta: beta.to_vec(),
+            cov,
+            n_free: coef.len(),
+            convergence: Convergence {
+                status: ConvergenceStatus::Converged,
+                iterations: 0,
+                objective: f64::NAN,
+                gradient_norm: f64::NAN,
+            },
+            sigma2,
+            loglik,
+            nobs,
+            residuals,
+            fitted,
+            x: x.to_vec(),
+            xreg: xreg.to_vec(),
+        }
+    }
+
+    /// Replace the conditional estimates of the innovation variance and the log-likelihood,
+    /// e.g. by the exact ones from `kalman::arma_loglik`.
+    pub(crate) fn with_likelihood(mut self, sigma2: f64, loglik: f64, nobs: usize) -> Self {
+        self.sigma2 = sigma2;
+        self.loglik = loglik;
+        self.nobs = nobs;
+        self
+    }
+
+    /// Set the covariance matrix of the estimated coefficients with the indices `free` in
+    /// `coef`. The other coefficients were fixed and have no variance.
+    pub(crate) fn with_covariance(mut self, cov: Vec<Vec<f64>>, free: &[usize]) -> Self {
+        for (a, &i) in free.iter().enumerate() {
+            for (b, &j) in free.iter().enumerate() {
+                self.cov[i][j] = cov[a][b];
+            }
+        }
+        self.n_free = free.len();
+        self
+    }
+
+    /// Set the convergence report of the (last) optimization.
+    pub(crate) fn with_convergence(mut self, convergence: Convergence) -> Self {
+        self.convergence = convergence;
+        self
+    }
+
+    /// Model order (p, d, q).
+    pub fn order(&self) -> (usize, usize, usize) {
+        self.order
+    }
+
+    /// Seasonal order (P, D, Q) and period s. All zeros for non-seasonal models.
+    pub fn seasonal_order(&self) -> SeasonalOrder {
+        self.seasonal
+    }
+
+    /// Intercept of the model on the differenced series.
+    pub fn intercept(&self) -> f64 {
+        self.intercept
+    }
+
+    /// AR coefficients phi_1..phi_p.
+    pub fn phi(&self) -> &[f64] {
+        &self.phi
+    }
+
+    /// MA coefficients theta_1..theta_q.
+    pub fn theta(&self) -> &[f64] {
+        &self.theta
+    }
+
+    /// Seasonal AR coefficients Phi_1..Phi_P.
+    pub fn seasonal_phi(&self) -> &[f64] {
+        &self.sphi
+    }
+
+    /// Seasonal MA coefficients Theta_1..Theta_Q.
+    pub fn seasonal_theta(&self) -> &[f64] {
+        &self.stheta
+    }
+
+    /// Regression coefficients of the exogenous regressors.
+    pub fn xreg_coef(&self) -> &[f64] {
+        &self.beta
+    }
+
+    /// Estimated innovation variance.
+    pub fn sigma2(&self) -> f64 {
+        self.sigma2
+    }
+
+    /// Gaussian log-likelihood at the estimated coefficients.
+    pub fn loglik(&self) -> f64 {
+        self.loglik
+    }
+
+    /// Akaike information criterion `-2 loglik + 2k`, where `k` is the number of
+    /// estimated coefficients plus one for the innovation variance.
+    pub fn aic(&self) -> f64 {
+        -2.0 * self.loglik + 2.0 * self.n_params() as f64
+    }
+
+    /// AIC with the small sample correction of Hurvich and Tsai (1989),
+    /// `AIC + 2k(k+1)/(n-k-1)`. Infinite if there are not enough observations.
+    pub fn aicc(&self) -> f64 {
+        let k = self.n_params() as f64;
+        let n = self.nobs as f64;
+        if n - k - 1.0 <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.aic() + 2.0 * k * (k + 1.0) / (n - k - 1.0)
+    }
+
+    /// Bayesian information criterion `-2 loglik + k log(n)`.
+    pub fn bic(&self) -> f64 {
+        -2.0 * self.loglik + self.n_params() as f64 * (self.nobs as f64).ln()
+    }
+
+    /// Number of estimated parameters, including the innovation variance.
+    fn n_params(&self) -> usize {
+        self.n_free + 1
+    }
+
+    /// Number of observations used to estimate the model.
+    pub fn nobs(&self) -> usize {
+        self.nobs
+    }
+
+    /// In-sample residuals of the differenced series. For CSS estimates, the first `p+P*s`
+    /// items are zeros.
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+
+    /// In-sample one-step-ahead predictions on the original scale, aligned with the
+    /// last `n-d-D*s` observations of the series.
+    pub fn fitted_values(&self) -> &[f64] {
+        &self.fitted
+    }
+
+    /// All coefficients in a single vector: the intercept, followed by the AR, MA,
+    /// seasonal AR, seasonal MA and regression coefficients.
+    pub fn coef(&self) -> Vec<f64> {
+        let mut coef = vec![self.intercept];
+        coef.extend(&self.phi);
+        coef.extend(&self.theta);
+        coef.extend(&self.sphi);
+        coef.extend(&self.stheta);
+        coef.extend(&self.beta);
+        coef
+    }
+
+    /// Moduli of the roots of the AR polynomial `1 - phi_1 z - ... - phi_p z^p` in
+    /// increasing order. For seasonal models, this is the expanded polynomial. The AR part
+    /// is stationary if all moduli are larger than one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let moduli = model.ar_root_moduli();
+    /// assert!((moduli[0] - 1.0 / model.phi()[0].abs()).abs() < 1.0e-7);
+    /// ```
+    pub fn ar_root_moduli(&self) -> Vec<f64> {
+        let (phi, _theta) = self.expanded();
+        poly::sorted_moduli(&poly::ar_roots(&phi))
+    }
+
+    /// Moduli of the roots of the MA polynomial `1 + theta_1 z + ... + theta_q z^q` in
+    /// increasing order. For seasonal models, this is the expanded polynomial. The MA part
+    /// is invertible if all moduli are larger than one.
+    pub fn ma_root_moduli(&self) -> Vec<f64> {
+        let (_phi, theta) = self.expanded();
+        poly::sorted_moduli(&poly::ma_roots(&theta))
+    }
+
+    /// Pairs of AR and MA roots (of the expanded polynomials) that are closer than
+    /// `tolerance`, a sign of an over-parameterized model. See `poly::cancelling_roots`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 0, 1).unwrap();
+    /// if !model.cancelling_roots(0.1).is_empty() {
+    ///     println!("Consider a model of lower order");
+    /// }
+    /// ```
+    pub fn cancelling_roots(&self, tolerance: f64) -> Vec<(Complex<f64>, Complex<f64>)> {
+        let (phi, theta) = self.expanded();
+        poly::cancelling_roots(&phi, &theta, tolerance)
+    }
+
+    /// Convergence report of the optimizer. For CSS-ML, this is the report of the maximum
+    /// likelihood stage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let convergence = model.convergence();
+    /// println!("{:?} after {} iterations", convergence.status, convergence.iterations);
+    /// ```
+    pub fn convergence(&self) -> Convergence {
+        self.convergence
+    }
+
+    /// The first `k` psi-weights of the fitted ARMA model of the differenced series, i.e. the
+    /// impulse responses of the innovations. For seasonal models, these are the weights of
+    /// the expanded polynomials. See `arma::psi_weights`.
+    pub fn psi_weights(&self, k: usize) -> Vec<f64> {
+        let (phi, theta) = self.expanded();
+        arma::psi_weights(&phi, &theta, k)
+    }
+
+    /// The first `k` pi-weights of the fitted ARMA model of the differenced series. For
+    /// seasonal models, these are the weights of the expanded polynomials. See
+    /// `arma::pi_weights`.
+    pub fn pi_weights(&self, k: usize) -> Vec<f64> {
+        let (phi, theta) = self.expanded();
+        arma::pi_weights(&phi, &theta, k)
+    }
+
+    /// Spectral density of the fitted ARMA model of the differenced series at the given
+    /// frequencies in cycles per observation, using the estimated innovation variance. For
+    /// seasonal models, this is the density of the expanded polynomials. See
+    /// `spectrum::arma_spectrum`.
+    pub fn spectral_density(&self, freq: &[f64]) -> Vec<f64> {
+        let (phi, theta) = self.expanded();
+        spectrum::arma_spectrum(&phi, &theta, self.sigma2, freq)
+    }
+
+    /// Estimated covariance matrix of the coefficients, in the order of `coef`. This is the
+    /// inverse of the numerical Hessian of the negative log-likelihood at the estimate, using
+    /// the conditional likelihood for CSS and the exact likelihood for ML estimates.
+    /// The entries are NaN if the Hessian is singular and for fixed coefficients.
+    pub fn cov(&self) -> &[Vec<f64>] {
+        &self.cov
+    }
+
+    /// Standard errors of the coefficients, in the order of `coef`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let se = model.std_errors();
+    /// assert_eq!(se.len(), 2);
+    /// assert!(se[1] > 0.0);
+    /// ```
+    pub fn std_errors(&self) -> Vec<f64> {
+        (0..self.cov.len()).map(|i| self.cov[i][i].sqrt()).collect()
+    }
+
+    /// z-statistics of the coefficients, i.e. the estimates divided by their standard errors.
+    pub fn z_stats(&self) -> Vec<f64> {
+        self.coef()
+            .iter()
+            .zip(self.std_errors())
+            .map(|(c, se)| c / se)
+            .collect()
+    }
+
+    /// Two-sided p-values of the coefficients for the null hypothesis that the coefficient
+    /// is zero, based on the asymptotic normal distribution of the z-statistics.
+    pub fn p_values(&self) -> Vec<f64> {
+        self.z_stats()
+            .iter()
+            .map(|z| 2.0 * stats::normal_cdf(-z.abs()))
+            .collect()
+    }
+
+    /// Check the residuals for remaining autocorrelation with the Ljung-Box and Box-Pierce
+    /// tests. The degrees of freedom are corrected for the fitted ARMA coefficients. Only the
+    /// residuals of the observations used in the fit are tested.
+    ///
+    /// # Arguments
+    ///
+    /// * `lags` - Number of lags h. Defaults to 10 for non-seasonal and 2s for seasonal
+    ///   models, at most n/5, but at least the number of ARMA coefficients plus 3.
+    ///
+    /// # Returns
+    ///
+    /// * Test results and the auto-correlations of the residuals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x: Vec<f64> = (0..100).map(|t| (t as f64 / 5.0).sin()).collect();
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let diagnostics = model.diagnostics(None).unwrap();
+    /// assert_eq!(diagnostics.ljung_box.df, 9);
+    /// println!("Ljung-Box p-value: {:.4}", diagnostics.ljung_box.p_value);
+    /// ```
+    pub fn diagnostics(&self, lags: Option<usize>) -> Result<Diagnostics> {
+        let residuals = &self.residuals[self.residuals.len() - self.nobs..];
+        let fitdf = self.phi.len() + self.theta.len() + self.sphi.len() + self.stheta.len();
+        let lags = lags.unwrap_or_else(|| {
+            let default = if self.seasonal.period > 1 {
+                2 * self.seasonal.period
+            } else {
+                10
+            };
+            default.min(residuals.len() / 5).max(fitdf + 3)
+        });
+        Ok(Diagnostics {
+            ljung_box: diagnostics::ljung_box(residuals, lags, fitdf)?,
+            box_pierce: diagnostics::box_pierce(residuals, lags, fitdf)?,
+            residual_acf: acf::acf(residuals, Some(lags), false)?,
+        })
+    }
+
+    /// Forecast the series. Returns the conditional mean of the next `n` values,
+    /// continuing from the end of the series the model was fitted on.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of steps to forecast.
+    ///
+    /// # Returns
+    ///
+    /// * Output vector of length n containing the forecasts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0];
+    /// let model = estimate::fit(&x, 1, 0, 0).unwrap();
+    /// let forecast = model.forecast(3).unwrap();
+    /// assert_eq!(forecast.len(), 3);
+    /// ```
+    pub fn forecast(&self, n: usize) -> Result<Vec<f64>> {
+        if !self.beta.is_empty() {
+            anyhow::bail!("Model has regressors, use `forecast_xreg` instead");
+        }
+        self.forecast_levels(&self.x, n)
+    }
+
+    /// Forecast a regression with ARIMA errors. Returns the conditional mean of the next
+    /// values, given the future values of the regressors.
+    ///
+    /// # Arguments
+    ///
+    /// * `&xreg` - Future values of the regressors, one vector per regressor. All vectors
+    ///   must have the length of the forecast horizon.
+    ///
+    /// # Returns
+    ///
+    /// * Output vector containing the forecasts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate::{self, FitOptions};
+    /// let x = [1.2, 2.9, 1.1, 3.2, 0.8, 3.1, 1.0, 2.8];
+    /// let z = vec![vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0]];
+    /// let model = estimate::fit_with_options(&x, 1, 0, 0, &FitOptions::new().with_xreg(z)).unwrap();
+    /// let forecast = model.forecast_xreg(&[vec![0.0, 1.0]]).unwrap();
+    /// assert!(forecast[0] < forecast[1]);
+    /// ```
+    pub fn forecast_xreg(&self, xreg: &[Vec<f64>]) -> Result<Vec<f64>> {
+        if xreg.len() != self.beta.len() {
+            anyhow::bail!("Expected {} regressors", self.beta.len());
+        }
+        let n = xreg.first().map_or(0, |z| z.len());
+        if xreg.iter().any(|z| z.len() != n) {
+            anyhow::bail!("Regressors must have the same length");
+        }
+
+        // forecast the regression errors, then add the regression effects
+        let mut u = self.x.clone();
+        for (beta, z) in self.beta.iter().zip(&self.xreg) {
+            for (ut, zt) in u.iter_mut().zip(z) {
+                *ut -= beta * zt;
+            }
+        }
+        let mut forecast = self.forecast_levels(&u, n)?;
+        for (beta, z) in self.beta.iter().zip(xreg) {
+            for (ft, zt) in forecast.iter_mut().zip(z) {
+                *ft += beta * zt;
+            }
+        }
+        Ok(forecast)
+    }
+
+    /// Forecast the series with standard errors and prediction intervals. The standard
+    /// errors are computed from the psi-weights of the integrated model and the innovation
+    /// variance, treating the coefficients as known.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of steps to forecast.
+    /// * `&levels` - Confidence levels of the prediction intervals, each in (0, 1).
+    ///
+    /// # Returns
+    ///
+    /// * Forecasts with standard errors and one prediction interval per level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::estimate;
+    /// let x = [1.0, 1.2, 1.4, 1.6, 1.4, 1.2, 1.0, 1.1, 1.3, 1.2];
+    /// let model = estimate::fit(&x, 1, 1, 0).unwrap();
+    /// let forecast = model.predict(5, &[0.8, 0.95]).unwrap();
+    /// let interval = &forecast.intervals[1];
+    /// assert!(interval.lower[4] < forecast.mean[4] && forecast.mean[4] < interval.upper[4]);
+    /// ```
+    pub fn predict(&self, n: usize, levels: &[f64]) -> Result<Forecast> {
+        let mean = self.forecast(n)?;
+        self.with_intervals(mean, levels)
+    }
+
+    /// Forecast a regression with ARIMA errors with standard errors and prediction
+    /// intervals, given the future values of the regressors. The regression coefficients
+    /// are treated as known, see `predict`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&xreg` - Future values of the regressors, one vector per regressor. All vectors
+    ///   must have the length of the forecast horizon.
+    /// * `&levels` - Confidence levels of the prediction intervals, each in (0, 1).
+    ///
+    /// # Returns
+    ///
+    /// * Forecasts with standard errors and one prediction interval per level.
+    pub fn predict_xreg(&self, xreg: &[Vec<f64>], levels: &[f64]) -> Result<Forecast> {
+        let mean = self.forecast_xreg(xreg)?;
+        self.with_intervals(mean, levels)
+    }
+
+    /// Add standard errors and prediction intervals to the point forecasts `mean`.
+    fn with_intervals(&self, mean: Vec<f64>, levels: &[f64]) -> Result<Forecast> {
+        if levels.iter().any(|l| !(*l > 0.0 && *l < 1.0)) {
+            anyhow::bail!("Confidence levels must be in (0, 1)");
+        }
+
+        // psi-weights of the integrated model phi(B) delta(B) x_t = theta(B) e_t
+        let s = self.seasonal.period;
+        let (phi, theta) = self.expanded();
+        let delta = util::differencing_polynomial(self.order.1, self.seasonal.d, s);
+        let mut ar = vec![0.0; phi.len() + delta.len()];
+        for (i, a) in std::iter::once(1.0)
+            .chain(phi.iter().map(|p| -p))
+            .enumerate()
+        {
+            for (k, b) in delta.iter().enumerate() {
+                ar[i + k] -= a * b;
+            }
+        }
+        let n = mean.len();
+        let mut psi = vec![1.0];
+        psi.extend(arma::psi_weights(&ar[1..], &theta, n.saturating_sub(1)));
+        psi.truncate(n);
+
+        let mut var = 0.0;
+        let std_errors: Vec<f64> = psi
+            .iter()
+            .map(|p| {
+                var += p * p;
+                (self.sigma2 * var).sqrt()
+            })
+            .collect();
+
+        let intervals = levels
+            .iter()
+            .map(|&level| {
+                let z = stats::normal_ppf(0.5 + 0.5 * level);
+                PredictionInterval {
+                    level,
+                    lower: mean
+                        .iter()
+                        .zip(&std_errors)
+                        .map(|(m, e)| m - z * e)
+                        .collect(),
+                    upper: mean
+                        .iter()
+                        .zip(&std_errors)
+                        .map(|(m, e)| m + z * e)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(Forecast {
+            mean,
+            std_errors,
+            intervals,
+        })
+    }
+
+    /// Forecast the ARIMA process `x` from the end of the series.
+    fn forecast_levels(&self, x: &[f64], n: usize) -> Result<Vec<f64>> {
+        let (_, d, _) = self.order;
+        let s = self.seasonal.period;
+        let (phi, theta) = self.expanded();
+
+        let w = util::diff(x, d);
+        let w = util::diff_seasonal(&w, self.seasonal.d, s);
+        let n_past = w.len();
+
+        if n_past < phi.len() || n_past < theta.len() {
+            anyhow::bail!("Time series is too short to forecast");
+        }
+
+        // future innovations are zero in expectation
+        let mut w = w;
+        let mut e = self.residuals.clone();
+        for i in n_past..n_past + n {
+            let mut wt = self.intercept;
+            for j in 0..phi.len() {
+                wt += phi[j] * w[i - j - 1];
+            }
+            for j in 0..theta.len() {
+                wt += theta[j] * e[i - j - 1];
+            }
+            w.push(wt);
+            e.push(0.0);
+        }
+
+        // undo the differencing
+        util::integrate(x, &w[n_past..], d, self.seasonal.d, s)
+    }
+
+    /// Summarize the fitted model in a human readable table.
+    pub fn summary(&self) -> String {
+        let (p, d, q) = self.order;
+        let mut s = format!("ARIMA({},{},{})", p, d, q);
+        let seasonal = self.seasonal;
+        if seasonal.p + seasonal.d + seasonal.q > 0 {
+            s += &format!(
+                "({},{},{})[{}]",
+                seasonal.p, seasonal.d, seasonal.q, seasonal.period
+            );
+        }
+        s += "\nCoefficients:\n";
+        s += &format!(
+            "  {:<10} {:>12} {:>12} {:>10} {:>10}\n",
+            "", "estimate", "s.e.", "z", "p-value"
+        );
+        let mut names = vec!["intercept".to_string()];
+        let groups = [
+            ("ar", p),
+            ("ma", q),
+            ("sar", seasonal.p),
+            ("sma", seasonal.q),
+            ("xreg", self.beta.len()),
+        ];
+        for (prefix, n) in groups {
+            names.extend((1..=n).map(|i| format!("{}{}", prefix, i)));
+        }
+        let se = self.std_errors();
+        let z = self.z_stats();
+        let pv = self.p_values();
+        for (i, coef) in self.coef().iter().enumerate() {
+            s += &format!(
+                "  {:<10} {:>12.6} {:>12.6} {:>10.3} {:>10.4}\n",
+                names[i], coef, se[i], z[i], pv[i]
+            );
+        }
+        s += &format!(
+            "sigma^2 = {:.6}, log likelihood = {:.4}, nobs = {}\n",
+            self.sigma2, self.loglik, self.nobs
+        );
+        s += &format!(
+            "AIC = {:.4}, AICc = {:.4}, BIC = {:.4}\n",
+            self.aic(),
+            self.aicc(),
+            self.bic()
+        );
+        s
+    }
+
+    /// Expanded AR and MA polynomials of the seasonal and non-seasonal parts.
+    fn expanded(&self) -> (Vec<f64>, Vec<f64>) {
+        let s = self.seasonal.period;
+        let phi = util::expand_seasonal_ar(&self.phi, &self.sphi, s);
+        let theta = util::expand_seasonal_ma(&self.theta, &self.stheta, s);
+        (phi, theta)
+    }
+}
+
+impl fmt::Display for FittedArima {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
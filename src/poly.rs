@@ -0,0 +1,225 @@
+use num::complex::Complex;
+
+/// Calculate the complex roots of the polynomial `c_0 + c_1 z + ... + c_n z^n` with the
+/// Durand-Kerner method. Trailing zero coefficients are ignored, so the number of roots is
+/// the actual degree.
+///
+/// # Arguments
+///
+/// * `&coef` - Coefficients c_0, ..., c_n in increasing order of the powers.
+///
+/// # Returns
+///
+/// * Output vector containing the roots, in no particular order.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// // 2 - 3z + z^2 = (z - 1)(z - 2)
+/// let mut roots: Vec<f64> = poly::roots(&[2.0, -3.0, 1.0]).iter().map(|z| z.re).collect();
+/// roots.sort_by(|a, b| a.total_cmp(b));
+/// assert!((roots[0] - 1.0).abs() < 1.0e-12);
+/// assert!((roots[1] - 2.0).abs() < 1.0e-12);
+/// ```
+pub fn roots(coef: &[f64]) -> Vec<Complex<f64>> {
+    let n = match coef.iter().rposition(|c| *c != 0.0) {
+        Some(n) if n > 0 => n,
+        _ => return Vec::new(),
+    };
+    // monic polynomial
+    let monic: Vec<f64> = coef[..=n].iter().map(|c| c / coef[n]).collect();
+    let eval = |z: Complex<f64>| {
+        monic
+            .iter()
+            .rev()
+            .fold(Complex::new(0.0, 0.0), |acc, c| acc * z + c)
+    };
+
+    // start on a circle enclosing all roots (Cauchy bound), at non-symmetric angles
+    let radius = 1.0 + monic[..n].iter().fold(0.0_f64, |m, c| m.max(c.abs()));
+    let mut z: Vec<Complex<f64>> = (0..n)
+        .map(|k| {
+            Complex::from_polar(
+                radius,
+                0.4 + 2.0 * std::f64::consts::PI * k as f64 / n as f64,
+            )
+        })
+        .collect();
+
+    for _ in 0..1000 {
+        let mut change: f64 = 0.0;
+        for i in 0..n {
+            let mut denom = Complex::new(1.0, 0.0);
+            for j in 0..n {
+                if i != j {
+                    denom *= z[i] - z[j];
+                }
+            }
+            if denom.norm() == 0.0 {
+                continue;
+            }
+            let step = eval(z[i]) / denom;
+            z[i] -= step;
+            change = change.max(step.norm() / z[i].norm().max(1.0));
+        }
+        if change < 1.0e-14 {
+            break;
+        }
+    }
+    z
+}
+
+/// Calculate the moduli of the roots of the polynomial `c_0 + c_1 z + ... + c_n z^n`.
+///
+/// # Arguments
+///
+/// * `&coef` - Coefficients c_0, ..., c_n in increasing order of the powers.
+///
+/// # Returns
+///
+/// * Output vector containing the moduli in increasing order.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// // 1 + z^2 has the roots i and -i
+/// let moduli = poly::root_moduli(&[1.0, 0.0, 1.0]);
+/// assert!((moduli[0] - 1.0).abs() < 1.0e-12);
+/// assert!((moduli[1] - 1.0).abs() < 1.0e-12);
+/// ```
+pub fn root_moduli(coef: &[f64]) -> Vec<f64> {
+    sorted_moduli(&roots(coef))
+}
+
+/// Moduli of the roots in increasing order, see `root_moduli`.
+pub(crate) fn sorted_moduli(roots: &[Complex<f64>]) -> Vec<f64> {
+    let mut moduli: Vec<f64> = roots.iter().map(|z| z.norm()).collect();
+    moduli.sort_by(|a, b| a.total_cmp(b));
+    moduli
+}
+
+/// Calculate the roots of the AR characteristic polynomial `1 - phi_1 z - ... - phi_p z^p`.
+/// For seasonal models, pass the expanded polynomial, see `util::expand_seasonal_ar`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector.
+///
+/// # Returns
+///
+/// * Output vector containing the roots.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// let roots = poly::ar_roots(&[0.5]);
+/// assert!((roots[0].re - 2.0).abs() < 1.0e-12);
+/// ```
+pub fn ar_roots(phi: &[f64]) -> Vec<Complex<f64>> {
+    let mut coef = vec![1.0];
+    coef.extend(phi.iter().map(|a| -a));
+    roots(&coef)
+}
+
+/// Calculate the roots of the MA characteristic polynomial `1 + theta_1 z + ... +
+/// theta_q z^q`. For seasonal models, pass the expanded polynomial, see
+/// `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&theta` - MA parameter vector.
+///
+/// # Returns
+///
+/// * Output vector containing the roots.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// let roots = poly::ma_roots(&[0.5]);
+/// assert!((roots[0].re + 2.0).abs() < 1.0e-12);
+/// ```
+pub fn ma_roots(theta: &[f64]) -> Vec<Complex<f64>> {
+    let mut coef = vec![1.0];
+    coef.extend(theta);
+    roots(&coef)
+}
+
+/// Check if an AR polynomial is stationary, i.e. all roots of `1 - phi_1 z - ... -
+/// phi_p z^p` lie outside of the unit circle.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// assert!(poly::is_stationary(&[0.5, 0.3]));
+/// assert!(!poly::is_stationary(&[0.5, 0.5]));
+/// ```
+pub fn is_stationary(phi: &[f64]) -> bool {
+    ar_roots(phi).iter().all(|z| z.norm() > 1.0)
+}
+
+/// Check if an MA polynomial is invertible, i.e. all roots of `1 + theta_1 z + ... +
+/// theta_q z^q` lie outside of the unit circle.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// assert!(poly::is_invertible(&[0.5]));
+/// assert!(!poly::is_invertible(&[-1.5]));
+/// ```
+pub fn is_invertible(theta: &[f64]) -> bool {
+    ma_roots(theta).iter().all(|z| z.norm() > 1.0)
+}
+
+/// Find pairs of AR and MA roots that nearly cancel. If the AR and MA polynomials share a
+/// root, the factor cancels in `theta(B) / phi(B)` and the model is over-parameterized:
+/// an ARMA(p-1, q-1) model describes the same process.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector.
+/// * `&theta` - MA parameter vector.
+/// * `tolerance` - Maximum distance of two roots in the complex plane.
+///
+/// # Returns
+///
+/// * Output vector of pairs (AR root, MA root) closer than the tolerance. Each root is part
+///   of at most one pair.
+///
+/// # Example
+///
+/// ```
+/// use arima::poly;
+/// // (1 - 0.5B) x_t = (1 - 0.49B) e_t is almost white noise
+/// let pairs = poly::cancelling_roots(&[0.5], &[-0.49], 0.1);
+/// assert_eq!(pairs.len(), 1);
+/// assert!(poly::cancelling_roots(&[0.5], &[0.5], 0.1).is_empty());
+/// ```
+pub fn cancelling_roots(
+    phi: &[f64],
+    theta: &[f64],
+    tolerance: f64,
+) -> Vec<(Complex<f64>, Complex<f64>)> {
+    let mut ma = ma_roots(theta);
+    let mut pairs = Vec::new();
+    for a in ar_roots(phi) {
+        // match with the closest remaining MA root
+        let closest = ma
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i, (a - m).norm()))
+            .min_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((i, distance)) = closest {
+            if distance < tolerance {
+                pairs.push((a, ma.remove(i)));
+            }
+        }
+    }
+    pairs
+}
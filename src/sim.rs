@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use crate::util;
+use crate::{estimate, util};
 use rand::Rng;
 
 /// Simulate an ARIMA model time series
@@ -43,17 +43,87 @@ pub fn arima_sim<T: Rng>(
     noise_fn: &dyn Fn(&mut T) -> f64,
     rng: &mut T,
 ) -> Result<Vec<f64>> {
+    sarima_sim(n, ar, ma, d, &Seasonal::default(), noise_fn, rng)
+}
+
+/// Seasonal part of a SARIMA(p, d, q)(P, D, Q)_s model, used by `sarima_sim` and
+/// `sarima_forecast`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Seasonal<'a> {
+    /// Model parameters for the seasonal AR part
+    pub ar: Option<&'a [f64]>,
+    /// Model parameters for the seasonal MA part
+    pub ma: Option<&'a [f64]>,
+    /// Model parameter for the seasonal differences
+    pub d: usize,
+    /// Number of observations per season
+    pub period: usize,
+}
+
+impl Seasonal<'_> {
+    /// Expand the seasonal and non-seasonal polynomials into single AR and MA polynomials.
+    fn expand(&self, ar: Option<&[f64]>, ma: Option<&[f64]>) -> (Vec<f64>, Vec<f64>) {
+        let ar = util::expand_seasonal_ar(ar.unwrap_or(&[]), self.ar.unwrap_or(&[]), self.period);
+        let ma = util::expand_seasonal_ma(ma.unwrap_or(&[]), self.ma.unwrap_or(&[]), self.period);
+        (ar, ma)
+    }
+}
+
+/// Simulate a seasonal ARIMA model time series
+///
+/// # Arguments
+///
+/// * `n` - Length of the time series
+/// * `ar` - Model parameters for the AR part
+/// * `ma` - Model parameters for the MA part
+/// * `d` - Model parameter for the differences
+/// * `seasonal` - Seasonal part of the model
+/// * `noise_fn` - Function that takes a `Rng' as input and returns noise
+/// * `rng` - Reference to a mutable `Rng`.
+///
+/// # Returns
+///
+/// * Output vector of length n containing the time series data.
+///
+/// # Example
+///
+/// ```
+/// use rand::prelude::*;
+/// use rand_distr::{Distribution, Normal};
+///
+/// let normal = Normal::new(0.0, 2.0).unwrap();
+///
+/// let x = arima::sim::sarima_sim(
+///     100,
+///     Some(&[0.5]),
+///     None,
+///     0,
+///     &arima::sim::Seasonal { ar: None, ma: Some(&[0.4]), d: 1, period: 12 },
+///     &|mut rng| { normal.sample(&mut rng) },
+///     &mut thread_rng()
+/// ).unwrap();
+/// assert_eq!(x.len(), 100);
+/// ```
+pub fn sarima_sim<T: Rng>(
+    n: usize,
+    ar: Option<&[f64]>,
+    ma: Option<&[f64]>,
+    d: usize,
+    seasonal: &Seasonal,
+    noise_fn: &dyn Fn(&mut T) -> f64,
+    rng: &mut T,
+) -> Result<Vec<f64>> {
+    let sd = seasonal.d * seasonal.period;
+    if n < d + sd {
+        anyhow::bail!("Time series must be at least as long as the order of differencing");
+    }
+
     let mut x: Vec<f64> = Vec::new();
 
-    // get orders
-    let ar_order = match ar {
-        Some(par) => par.len(),
-        None => 0_usize,
-    };
-    let ma_order = match ma {
-        Some(par) => par.len(),
-        None => 0_usize,
-    };
+    // get expanded parameters and orders
+    let (ar, ma) = seasonal.expand(ar, ma);
+    let ar_order = ar.len();
+    let ma_order = ma.len();
 
     // create some noise for the startup
     let burn_in = ar_order + ma_order + 10;
@@ -64,7 +134,6 @@ pub fn arima_sim<T: Rng>(
 
     // create further noise and calculate MA part
     if ma_order > 0 {
-        let ma = ma.unwrap();
         // x currently contains only noise
         // copy into noise vector for MA regression
         let noise = x.clone();
@@ -83,8 +152,6 @@ pub fn arima_sim<T: Rng>(
 
     // calculate AR part
     if ar_order > 0 {
-        let ar = ar.unwrap();
-
         // the first 0..ma_order+ar_order are not regressed
         for i in ma_order + ar_order..burn_in + n {
             for j in 0..ar_order {
@@ -94,9 +161,10 @@ pub fn arima_sim<T: Rng>(
     }
 
     // remove burn_in part from vector, calculate differences
-    if d > 0 {
-        // also remove last d elements as there will be d zeros at the start
-        x = util::diffinv(&x[burn_in..x.len() - d], d);
+    if d + sd > 0 {
+        // also remove last d+D*s elements as there will be d+D*s zeros at the start
+        x = util::diffinv_seasonal(&x[burn_in..x.len() - d - sd], seasonal.d, seasonal.period);
+        x = util::diffinv(&x, d);
     } else {
         x.drain(0..burn_in);
     }
@@ -108,7 +176,8 @@ pub fn arima_sim<T: Rng>(
 ///
 /// # Arguments
 ///
-/// * `ts` - Time series to forecast from
+/// * `ts` - Time series to forecast from. The MA part uses the in-sample residuals of
+///   the model on `ts` as past shocks.
 /// * `n` - Length to forecast
 /// * `ar` - Model parameters for the AR part
 /// * `ma` - Model parameters for the MA part
@@ -118,7 +187,7 @@ pub fn arima_sim<T: Rng>(
 ///
 /// # Returns
 ///
-/// * Output vector of length n containing the time series data.
+/// * Output vector of length n containing the forecast, continuing from the end of `ts`.
 ///
 /// # Example
 ///
@@ -149,39 +218,95 @@ pub fn arima_forecast<F: Fn(usize, &mut T) -> f64, T: Rng>(
     noise_fn: &F,
     rng: &mut T,
 ) -> Result<Vec<f64>> {
-    let n_past = ts.len();
-    let mut x = ts.to_vec();
-
-    // get orders
-    let ar_order = match ar {
-        Some(par) => par.len(),
-        None => 0_usize,
-    };
-    let ma_order = match ma {
-        Some(par) => par.len(),
-        None => 0_usize,
-    };
+    sarima_forecast(ts, n, ar, ma, d, &Seasonal::default(), noise_fn, rng)
+}
+
+/// Forecast a seasonal ARIMA model time series
+///
+/// # Arguments
+///
+/// * `ts` - Time series to forecast from. The MA part uses the in-sample residuals of
+///   the model on `ts` as past shocks.
+/// * `n` - Length to forecast
+/// * `ar` - Model parameters for the AR part
+/// * `ma` - Model parameters for the MA part
+/// * `d` - Model parameter for the differences
+/// * `seasonal` - Seasonal part of the model
+/// * `noise_fn` - Function that takes a `Rng' as input and returns noise
+/// * `rng` - Reference to a mutable `Rng`.
+///
+/// # Returns
+///
+/// * Output vector of length n containing the forecast, continuing from the end of `ts`.
+///
+/// # Example
+///
+/// ```
+/// use rand::prelude::*;
+/// use rand_distr::{Distribution, Normal};
+///
+/// let normal = Normal::new(0.0, 2.0).unwrap();
+///
+/// let ts = [0.632, 0.594, -2.750, -5.389, -5.645, -7.672, -12.595, -18.260, -24.147, -31.427];
+///
+/// let x = arima::sim::sarima_forecast(
+///     &ts,
+///     100,
+///     Some(&[0.5]),
+///     None,
+///     0,
+///     &arima::sim::Seasonal { ar: Some(&[0.3]), ma: None, d: 0, period: 4 },
+///     &|i, mut rng| { normal.sample(&mut rng) },
+///     &mut thread_rng()
+/// ).unwrap();
+/// assert_eq!(x.len(), 100);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn sarima_forecast<F: Fn(usize, &mut T) -> f64, T: Rng>(
+    ts: &[f64],
+    n: usize,
+    ar: Option<&[f64]>,
+    ma: Option<&[f64]>,
+    d: usize,
+    seasonal: &Seasonal,
+    noise_fn: &F,
+    rng: &mut T,
+) -> Result<Vec<f64>> {
+    // get expanded parameters and orders
+    let (ar, ma) = seasonal.expand(ar, ma);
+    let ar_order = ar.len();
+    let ma_order = ma.len();
+
+    // the recursions run on the differenced series
+    let sd = seasonal.d * seasonal.period;
+    if ts.len() < d + sd + ar_order.max(ma_order) {
+        anyhow::bail!("Time series is too short to forecast");
+    }
+    let w = util::diff(ts, d);
+    let mut x = util::diff_seasonal(&w, seasonal.d, seasonal.period);
+    let n_past = x.len();
+
+    // the past shocks are the in-sample residuals
+    let mut noise = estimate::residuals(&x, 0.0, Some(&ar), Some(&ma))?;
 
     // initialize forecast with noise
     for i in 0..n {
         let e = noise_fn(i, rng);
         x.push(e);
+        noise.push(e);
     }
 
-    // create further noise and calculate MA part
+    // calculate MA part
     if ma_order > 0 {
-        let ma = ma.unwrap();
-        let x_ = x.clone();
         for i in n_past..n_past + n {
             for j in 0..ma_order {
-                x[i] += ma[j] * x_[i - j - 1];
+                x[i] += ma[j] * noise[i - j - 1];
             }
         }
     }
 
     // calculate AR part
     if ar_order > 0 {
-        let ar = ar.unwrap();
         for i in n_past..n_past + n {
             for j in 0..ar_order {
                 x[i] += ar[j] * x[i - j - 1];
@@ -189,14 +314,6 @@ pub fn arima_forecast<F: Fn(usize, &mut T) -> f64, T: Rng>(
         }
     }
 
-    // remove burn_in part from vector, calculate differences
-    if d > 0 {
-        x = util::diffinv(&x[n_past..x.len()], d);
-        // drop the d zeros at the start
-        x.drain(0..d);
-    } else {
-        x.drain(0..n_past);
-    }
-
-    Ok(x)
+    // integrate the forecast, continuing from the last observed values
+    util::integrate(ts, &x[n_past..], d, seasonal.d, seasonal.period)
 }
@@ -0,0 +1,381 @@
+use anyhow::Result;
+use num::complex::Complex;
+
+use std::f64::consts::PI;
+
+use crate::{acf, fft};
+
+/// Spectral density estimate at a grid of frequencies.
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    /// Frequencies in cycles per observation, between 0 and 0.5.
+    pub freq: Vec<f64>,
+    /// Estimated spectral density at the frequencies. The density integrates to the
+    /// variance of the series over [-0.5, 0.5], like in R's `spectrum`.
+    pub spec: Vec<f64>,
+    /// Equivalent degrees of freedom of the estimates, i.e. `df * spec / f` is approximately
+    /// chi-squared distributed for the true density `f`. None for model-based estimates.
+    pub df: Option<f64>,
+    /// Bandwidth of the smoothing kernel in cycles per observation. None for model-based
+    /// estimates.
+    pub bandwidth: Option<f64>,
+}
+
+/// Smoothing kernel for periodograms, see `SpectrumOptions::with_kernel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// Daniell kernel of half-width m, i.e. the moving average of 2m+1 values.
+    Daniell(usize),
+    /// Modified Daniell kernel of half-width m, i.e. the moving average of 2m+1 values
+    /// where the two end values have half the weight.
+    ModifiedDaniell(usize),
+}
+
+impl Kernel {
+    /// Weights of the kernel for the offsets -m, ..., m.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arima::spectrum::Kernel;
+    /// assert_eq!(Kernel::ModifiedDaniell(1).weights(), [0.25, 0.5, 0.25]);
+    /// ```
+    pub fn weights(&self) -> Vec<f64> {
+        match *self {
+            Kernel::Daniell(m) => vec![1.0 / (2 * m + 1) as f64; 2 * m + 1],
+            Kernel::ModifiedDaniell(0) => vec![1.0],
+            Kernel::ModifiedDaniell(m) => {
+                let mut w = vec![1.0 / (2 * m) as f64; 2 * m + 1];
+                w[0] /= 2.0;
+                w[2 * m] /= 2.0;
+                w
+            }
+        }
+    }
+}
+
+/// Options for estimating the spectral density with `periodogram_with_options`. The
+/// defaults give the raw periodogram of the demeaned series.
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum::{Kernel, SpectrumOptions};
+/// // like R's spectrum(x, spans = c(3, 5))
+/// let options = SpectrumOptions::new()
+///     .with_taper(0.1)
+///     .with_detrend(true)
+///     .with_kernel(Kernel::ModifiedDaniell(1))
+///     .with_kernel(Kernel::ModifiedDaniell(2));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpectrumOptions {
+    taper: f64,
+    detrend: bool,
+    kernels: Vec<Kernel>,
+}
+
+impl SpectrumOptions {
+    /// Create options for the raw periodogram.
+    pub fn new() -> SpectrumOptions {
+        Default::default()
+    }
+
+    /// Taper this proportion of the series at each end with a split cosine bell before
+    /// the transform, see `taper`. Has to be in [0, 0.5]. Defaults to 0.
+    pub fn with_taper(mut self, proportion: f64) -> SpectrumOptions {
+        self.taper = proportion;
+        self
+    }
+
+    /// Remove a linear trend instead of only the mean before the transform. Defaults to
+    /// false.
+    pub fn with_detrend(mut self, detrend: bool) -> SpectrumOptions {
+        self.detrend = detrend;
+        self
+    }
+
+    /// Smooth the periodogram with a kernel. Kernels added more than once are applied one
+    /// after the other. Defaults to no smoothing.
+    pub fn with_kernel(mut self, kernel: Kernel) -> SpectrumOptions {
+        self.kernels.push(kernel);
+        self
+    }
+}
+
+/// Calculate the raw periodogram `I(f) = |sum_t (x_t - mean) exp(-2 pi i f t)|^2 / n` of a
+/// time series of length n at the Fourier frequencies f = k/n, k = 1, ..., n/2.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+///
+/// # Returns
+///
+/// * Periodogram with two degrees of freedom per frequency.
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum;
+/// // a cosine with four cycles in 32 observations
+/// let x: Vec<f64> = (0..32).map(|t| (std::f64::consts::PI * t as f64 / 4.0).cos()).collect();
+/// let pgram = spectrum::periodogram(&x).unwrap();
+/// assert_eq!(pgram.freq.len(), 16);
+/// assert!((pgram.freq[3] - 0.125).abs() < 1.0e-12);
+/// assert!((pgram.spec[3] - 8.0).abs() < 1.0e-12);
+/// assert!(pgram.spec[2].abs() < 1.0e-12);
+/// ```
+pub fn periodogram(x: &[f64]) -> Result<Spectrum> {
+    periodogram_with_options(x, &SpectrumOptions::default())
+}
+
+/// Estimate the spectral density of a time series of length n from its periodogram at the
+/// Fourier frequencies f = k/n, k = 1, ..., n/2, with optional tapering and smoothing,
+/// like R's `spec.pgram`. The smoothing wraps around the ends of the frequency range.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `&options` - Tapering, detrending and smoothing, see `SpectrumOptions`.
+///
+/// # Returns
+///
+/// * Spectral density estimate.
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum::{self, Kernel, SpectrumOptions};
+/// let x: Vec<f64> = (0..100).map(|t| ((t * 7919) % 113) as f64).collect();
+/// let options = SpectrumOptions::new().with_taper(0.1).with_kernel(Kernel::Daniell(2));
+/// let spec = spectrum::periodogram_with_options(&x, &options).unwrap();
+/// assert_eq!(spec.freq.len(), 50);
+/// ```
+pub fn periodogram_with_options(x: &[f64], options: &SpectrumOptions) -> Result<Spectrum> {
+    let n = x.len();
+    if n < 2 {
+        anyhow::bail!("Time series must have at least two values");
+    }
+    if !(0.0..=0.5).contains(&options.taper) {
+        anyhow::bail!("Taper proportion must be in [0, 0.5]");
+    }
+
+    // remove mean and trend, t is centered so that both are orthogonal
+    let mean = x.iter().sum::<f64>() / n as f64;
+    let mut z: Vec<f64> = x.iter().map(|xi| xi - mean).collect();
+    if options.detrend {
+        let t = |i: usize| i as f64 - (n - 1) as f64 / 2.0;
+        let sum_t2 = n as f64 * ((n * n) as f64 - 1.0) / 12.0;
+        let slope = z.iter().enumerate().map(|(i, zi)| zi * t(i)).sum::<f64>() / sum_t2;
+        for (i, zi) in z.iter_mut().enumerate() {
+            *zi -= slope * t(i);
+        }
+    }
+    let z = taper(&z, options.taper);
+
+    // periodogram at all frequencies k/n, k = 0, ..., n-1
+    let buf: Vec<Complex<f64>> = z.iter().map(|zi| Complex::new(*zi, 0.0)).collect();
+    let mut pgram: Vec<f64> = fft::dft(&buf)
+        .iter()
+        .map(|c| c.norm_sqr() / n as f64)
+        .collect();
+    // frequency zero is meaningless after demeaning, interpolate it for the smoothing
+    pgram[0] = 0.5 * (pgram[1] + pgram[n - 1]);
+
+    // combine the kernels and smooth circularly
+    let mut weights = vec![1.0];
+    for kernel in options.kernels.iter() {
+        weights = convolve(&weights, &kernel.weights());
+    }
+    let m = weights.len() / 2;
+    if weights.len() > n {
+        anyhow::bail!("Smoothing kernel is wider than the time series");
+    }
+    if m > 0 {
+        pgram = (0..n)
+            .map(|k| {
+                weights
+                    .iter()
+                    .enumerate()
+                    .map(|(j, w)| w * pgram[(k + n + j - m) % n])
+                    .sum()
+            })
+            .collect();
+    }
+
+    // corrections for the variance lost by tapering
+    let u2 = 1.0 - 5.0 / 8.0 * options.taper * 2.0;
+    let u4 = 1.0 - 93.0 / 128.0 * options.taper * 2.0;
+    let sum_w2: f64 = weights.iter().map(|w| w * w).sum();
+    let df = 2.0 / sum_w2 / (u4 / (u2 * u2));
+    let bandwidth = weights
+        .iter()
+        .enumerate()
+        .map(|(j, w)| {
+            let i = j as f64 - m as f64;
+            (1.0 / 12.0 + i * i) * w
+        })
+        .sum::<f64>()
+        .sqrt()
+        / n as f64;
+
+    let n_freq = n / 2;
+    Ok(Spectrum {
+        freq: (1..=n_freq).map(|k| k as f64 / n as f64).collect(),
+        spec: pgram[1..=n_freq].iter().map(|p| p / u2).collect(),
+        df: Some(df),
+        bandwidth: Some(bandwidth),
+    })
+}
+
+/// Apply a split cosine bell taper to a time series, like R's `spec.taper`. The first and
+/// last `floor(n * proportion)` values are multiplied with a half cosine bell that rises
+/// from zero to one.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `proportion` - Proportion of the series to taper at each end, in [0, 0.5].
+///
+/// # Returns
+///
+/// * Tapered series of the same length.
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum;
+/// let y = spectrum::taper(&[1.0; 10], 0.1);
+/// assert!((y[0] - 0.5).abs() < 1.0e-12);
+/// assert_eq!(y[1..9], [1.0; 8]);
+/// assert!((y[9] - 0.5).abs() < 1.0e-12);
+/// ```
+pub fn taper(x: &[f64], proportion: f64) -> Vec<f64> {
+    let n = x.len();
+    let m = (n as f64 * proportion).floor() as usize;
+    let mut y = x.to_vec();
+    for i in 0..m {
+        let w = 0.5 * (1.0 - (PI * (2 * i + 1) as f64 / (2 * m) as f64).cos());
+        y[i] *= w;
+        y[n - 1 - i] *= w;
+    }
+    y
+}
+
+/// Estimate the spectral density of a time series with an autoregressive model fitted by
+/// `acf::ar`, like R's `spec.ar`. If no order is given, it is selected by AIC among the
+/// orders up to `min(n - 1, 10 log10(n))`.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `order` - Order of the AR model, or None to select it by AIC.
+/// * `n_freq` - Number of equally spaced frequencies from 0 to 0.5, at least 2.
+///
+/// # Returns
+///
+/// * Spectral density of the fitted AR model.
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum;
+/// let x: Vec<f64> = (0..200).map(|t| ((t * 7919) % 113) as f64).collect();
+/// let spec = spectrum::ar_spectrum(&x, Some(2), 101).unwrap();
+/// assert_eq!(spec.freq.len(), 101);
+/// assert_eq!(spec.freq[100], 0.5);
+/// ```
+// `Option::is_none_or` needs Rust 1.82
+#[allow(clippy::unnecessary_map_or)]
+pub fn ar_spectrum(x: &[f64], order: Option<usize>, n_freq: usize) -> Result<Spectrum> {
+    let n = x.len();
+    if n < 2 {
+        anyhow::bail!("Time series must have at least two values");
+    }
+    if n_freq < 2 {
+        anyhow::bail!("Number of frequencies must be at least 2");
+    }
+
+    let (phi, var) = match order {
+        Some(order) => acf::ar(x, Some(order))?,
+        None => {
+            let max_order = ((10.0 * (n as f64).log10()).floor() as usize).min(n - 1);
+            let rho = acf::acf(x, Some(max_order), false)?;
+            let cov0 = acf::acf(x, Some(0), true)?[0];
+            let mut best: Option<(f64, (Vec<f64>, f64))> = None;
+            for p in 0..=max_order {
+                let (phi, var) = acf::ar_dl_rho_cov(&rho, cov0, Some(p))?;
+                let aic = n as f64 * var.ln() + 2.0 * p as f64;
+                if best.as_ref().map_or(true, |(a, _)| aic < *a) {
+                    best = Some((aic, (phi, var)));
+                }
+            }
+            best.unwrap().1
+        }
+    };
+
+    let freq: Vec<f64> = (0..n_freq)
+        .map(|k| 0.5 * k as f64 / (n_freq - 1) as f64)
+        .collect();
+    Ok(Spectrum {
+        spec: arma_spectrum(&phi, &[], var, &freq),
+        freq,
+        df: None,
+        bandwidth: None,
+    })
+}
+
+/// Calculate the theoretical spectral density
+/// `f(w) = sigma2 |theta(exp(-2 pi i w))|^2 / |phi(exp(-2 pi i w))|^2` of an ARMA process,
+/// like R's `spec.arma`. For seasonal models, pass the expanded polynomials, see
+/// `util::expand_seasonal_ar` and `util::expand_seasonal_ma`.
+///
+/// # Arguments
+///
+/// * `&phi` - AR parameter vector.
+/// * `&theta` - MA parameter vector.
+/// * `sigma2` - Innovation variance.
+/// * `&freq` - Frequencies in cycles per observation.
+///
+/// # Returns
+///
+/// * Spectral density at the frequencies. It integrates to the variance of the process over
+///   [-0.5, 0.5].
+///
+/// # Example
+///
+/// ```
+/// use arima::spectrum;
+/// // the spectrum of white noise is flat
+/// let spec = spectrum::arma_spectrum(&[], &[], 2.0, &[0.0, 0.25, 0.5]);
+/// assert_eq!(spec, [2.0, 2.0, 2.0]);
+/// // an AR(1) process with positive phi has most power at low frequencies
+/// let spec = spectrum::arma_spectrum(&[0.5], &[], 1.0, &[0.0, 0.5]);
+/// assert!((spec[0] - 4.0).abs() < 1.0e-12);
+/// assert!((spec[1] - 1.0 / 2.25).abs() < 1.0e-12);
+/// ```
+pub fn arma_spectrum(phi: &[f64], theta: &[f64], sigma2: f64, freq: &[f64]) -> Vec<f64> {
+    // value of 1 + sign * sum_j c_j z^j at z = exp(-2 pi i f)
+    let eval = |c: &[f64], sign: f64, f: f64| -> Complex<f64> {
+        let mut v = Complex::new(1.0, 0.0);
+        for (j, cj) in c.iter().enumerate() {
+            v += sign * cj * Complex::from_polar(1.0, -2.0 * PI * f * (j + 1) as f64);
+        }
+        v
+    };
+    freq.iter()
+        .map(|&f| sigma2 * eval(theta, 1.0, f).norm_sqr() / eval(phi, -1.0, f).norm_sqr())
+        .collect()
+}
+
+/// Full convolution of two kernels.
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut c = vec![0.0; a.len() + b.len() - 1];
+    for (i, ai) in a.iter().enumerate() {
+        for (j, bj) in b.iter().enumerate() {
+            c[i + j] += ai * bj;
+        }
+    }
+    c
+}
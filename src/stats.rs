@@ -0,0 +1,214 @@
+use std::f64::consts::PI;
+
+/// Cumulative distribution function of the standard normal distribution.
+///
+/// # Arguments
+///
+/// * `x` - Quantile.
+///
+/// # Returns
+///
+/// * Probability `P(Z <= x)` for a standard normal variable `Z`.
+///
+/// # Example
+///
+/// ```
+/// use arima::stats;
+/// assert!((stats::normal_cdf(0.0) - 0.5).abs() < 1.0e-12);
+/// assert!((stats::normal_cdf(1.959963984540054) - 0.975).abs() < 1.0e-12);
+/// ```
+pub fn normal_cdf(x: f64) -> f64 {
+    // Phi(x) = erfc(-x/sqrt(2))/2 and erfc(z) = Q(1/2, z^2) for z >= 0
+    let z = x / std::f64::consts::SQRT_2;
+    if x.is_nan() {
+        f64::NAN
+    } else if z < 0.0 {
+        0.5 * gamma_q(0.5, z * z)
+    } else {
+        1.0 - 0.5 * gamma_q(0.5, z * z)
+    }
+}
+
+/// Quantile function (inverse CDF) of the standard normal distribution.
+///
+/// # Arguments
+///
+/// * `p` - Probability in (0, 1).
+///
+/// # Returns
+///
+/// * Quantile `x` with `P(Z <= x) = p` for a standard normal variable `Z`. NaN if `p` is
+///   outside of [0, 1], and infinite for `p` equal to 0 or 1.
+///
+/// # Example
+///
+/// ```
+/// use arima::stats;
+/// assert!((stats::normal_ppf(0.975) - 1.959963984540054).abs() < 1.0e-12);
+/// ```
+pub fn normal_ppf(p: f64) -> f64 {
+    if p.is_nan() || !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    } else if p == 0.0 {
+        return f64::NEG_INFINITY;
+    } else if p == 1.0 {
+        return f64::INFINITY;
+    }
+
+    // rational approximation by P. J. Acklam with a relative error below 1.15e-9
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    let p_low = 0.02425;
+    let mut x = if p < p_low {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - p_low {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    };
+
+    // one step of Halley's method to full precision
+    let e = normal_cdf(x) - p;
+    let u = e * (2.0 * PI).sqrt() * (0.5 * x * x).exp();
+    x -= u / (1.0 + 0.5 * x * u);
+    x
+}
+
+/// Survival function `P(X > x)` of the chi-squared distribution.
+///
+/// # Arguments
+///
+/// * `x` - Quantile.
+/// * `df` - Degrees of freedom, positive.
+///
+/// # Returns
+///
+/// * Probability `P(X > x)` for a chi-squared variable `X` with `df` degrees of freedom.
+///
+/// # Example
+///
+/// ```
+/// use arima::stats;
+/// assert!((stats::chi2_sf(3.841458820694124, 1.0) - 0.05).abs() < 1.0e-12);
+/// assert!((stats::chi2_sf(2.0, 2.0) - (-1.0_f64).exp()).abs() < 1.0e-12);
+/// ```
+pub fn chi2_sf(x: f64, df: f64) -> f64 {
+    if x.is_nan() || df.is_nan() || df <= 0.0 {
+        f64::NAN
+    } else {
+        gamma_q(0.5 * df, 0.5 * x)
+    }
+}
+
+/// Logarithm of the gamma function for positive arguments (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut a = COEF[0];
+    let t = x + G + 0.5;
+    for (i, c) in COEF.iter().enumerate().skip(1) {
+        a += c / (x + i as f64);
+    }
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Regularized upper incomplete gamma function `Q(a, x) = 1 - P(a, x)`.
+/// Uses the series expansion of `P` for `x < a + 1` and a continued fraction otherwise.
+fn gamma_q(a: f64, x: f64) -> f64 {
+    const EPS: f64 = 1.0e-16;
+    const MAX_ITER: usize = 1000;
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let log_prefactor = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..MAX_ITER {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPS {
+                break;
+            }
+        }
+        1.0 - sum * log_prefactor.exp()
+    } else {
+        // modified Lentz's method
+        let tiny = 1.0e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITER {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPS {
+                break;
+            }
+        }
+        h * log_prefactor.exp()
+    }
+}
@@ -0,0 +1,364 @@
+use anyhow::Result;
+
+use crate::{linalg, stats, util};
+
+/// Deterministic terms included in the test regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Regression {
+    /// No deterministic terms. Only supported by the ADF test.
+    NoConstant,
+    /// A constant (level stationarity for the KPSS test).
+    #[default]
+    Constant,
+    /// A constant and a linear time trend (trend stationarity for the KPSS test).
+    ConstantTrend,
+}
+
+/// Number of lagged differences in the ADF regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagSelection {
+    /// Use a fixed number of lags.
+    Fixed(usize),
+    /// Minimize the AIC over `0..=12*(n/100)^(1/4)` lags (Schwert, 1989).
+    Aic,
+    /// Minimize the BIC over `0..=12*(n/100)^(1/4)` lags (Schwert, 1989).
+    Bic,
+}
+
+/// Result of a unit root or stationarity test.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// Value of the test statistic.
+    pub statistic: f64,
+    /// Approximate p-value of the test statistic.
+    pub p_value: f64,
+    /// Critical values as pairs of significance level and critical value.
+    pub critical_values: Vec<(f64, f64)>,
+    /// Number of lags used, i.e. lagged differences for the ADF test and the bandwidth of
+    /// the long-run variance estimate for the KPSS test.
+    pub lags: usize,
+    /// Number of observations used in the test regression.
+    pub nobs: usize,
+}
+
+/// Test used by `ndiffs` to decide whether to difference the series further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitRootTest {
+    /// KPSS test. The null hypothesis is stationarity.
+    #[default]
+    Kpss,
+    /// Augmented Dickey-Fuller test. The null hypothesis is a unit root.
+    Adf,
+}
+
+/// Augmented Dickey-Fuller test for a unit root. The statistic is the t-ratio of `gamma` in
+/// the regression `dx_t = [const] + [trend * t] + gamma * x_t-1 + sum_i delta_i * dx_t-i + e_t`.
+/// The null hypothesis of a unit root is rejected for small (negative) values.
+/// Critical values use the response surfaces of MacKinnon (2010), p-values the
+/// approximation of MacKinnon (1994).
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `regression` - Deterministic terms in the test regression.
+/// * `lags` - Number of lagged differences, fixed or selected by an information criterion.
+///
+/// # Returns
+///
+/// * Test statistic, p-value and critical values at the 1%, 5% and 10% levels.
+///
+/// # Example
+///
+/// ```
+/// use arima::unitroot::{self, LagSelection, Regression};
+/// let x: Vec<f64> = (0..100).map(|t| ((t * 7919) % 113) as f64).collect();
+/// let result = unitroot::adf(&x, Regression::Constant, LagSelection::Fixed(1)).unwrap();
+/// assert!(result.p_value < 0.01);
+/// ```
+pub fn adf(x: &[f64], regression: Regression, lags: LagSelection) -> Result<TestResult> {
+    let n = x.len();
+    let n_det = match regression {
+        Regression::NoConstant => 0,
+        Regression::Constant => 1,
+        Regression::ConstantTrend => 2,
+    };
+    let max_lag = match lags {
+        LagSelection::Fixed(k) => k,
+        _ => (12.0 * (n as f64 / 100.0).powf(0.25)) as usize,
+    };
+    // at least a few degrees of freedom in the largest regression
+    let max_lag = max_lag.min((n / 2).saturating_sub(n_det + 2));
+    if n < 2 * (n_det + 3) {
+        anyhow::bail!("Time series is too short");
+    }
+    if let LagSelection::Fixed(k) = lags {
+        if k > max_lag {
+            anyhow::bail!("Too many lags for the length of the time series");
+        }
+    }
+
+    let dx = util::diff(x, 1);
+
+    // regression of dx_t on the deterministic terms, x_t-1 and k lagged differences,
+    // for the observations t >= start
+    let regress = |k: usize, start: usize| {
+        let rows = start..dx.len();
+        let mut columns: Vec<Vec<f64>> = vec![rows.clone().map(|t| x[t]).collect()];
+        if n_det > 0 {
+            columns.push(vec![1.0; rows.len()]);
+        }
+        if n_det > 1 {
+            columns.push(rows.clone().map(|t| (t + 1) as f64).collect());
+        }
+        for i in 1..=k {
+            columns.push(rows.clone().map(|t| dx[t - i]).collect());
+        }
+        let y: Vec<f64> = rows.map(|t| dx[t]).collect();
+        linalg::ols(&columns, &y).map(|fit| (fit, y.len(), columns.len()))
+    };
+
+    let k = match lags {
+        LagSelection::Fixed(k) => k,
+        LagSelection::Aic | LagSelection::Bic => {
+            // compare all lags on the same observations
+            let mut best = (f64::INFINITY, 0);
+            for k in 0..=max_lag {
+                let (fit, nobs, n_params) = regress(k, max_lag)?;
+                let nobs = nobs as f64;
+                let penalty = if lags == LagSelection::Aic {
+                    2.0
+                } else {
+                    nobs.ln()
+                };
+                let ic = nobs * (fit.ssr / nobs).ln() + penalty * n_params as f64;
+                if ic < best.0 {
+                    best = (ic, k);
+                }
+            }
+            best.1
+        }
+    };
+
+    let (fit, nobs, _) = regress(k, k)?;
+    let statistic = fit.coef[0] / fit.std_errors[0];
+
+    // MacKinnon (2010), table 2 for a single variable: b0 + b1/T + b2/T^2 + b3/T^3
+    let surface: [[f64; 4]; 3] = match regression {
+        Regression::NoConstant => [
+            [-2.56574, -2.2358, -3.627, 0.0],
+            [-1.94100, -0.2686, -3.365, 31.223],
+            [-1.61682, 0.2656, -2.714, 25.364],
+        ],
+        Regression::Constant => [
+            [-3.43035, -6.5393, -16.786, -79.433],
+            [-2.86154, -2.8903, -4.234, -40.040],
+            [-2.56677, -1.5384, -2.809, 0.0],
+        ],
+        Regression::ConstantTrend => [
+            [-3.95877, -9.0531, -28.428, -134.155],
+            [-3.41049, -4.3904, -9.036, -45.374],
+            [-3.12705, -2.5856, -3.925, -22.380],
+        ],
+    };
+    let t = nobs as f64;
+    let critical_values = [0.01, 0.05, 0.1]
+        .iter()
+        .zip(&surface)
+        .map(|(level, b)| {
+            (
+                *level,
+                b[0] + b[1] / t + b[2] / t.powi(2) + b[3] / t.powi(3),
+            )
+        })
+        .collect();
+
+    Ok(TestResult {
+        statistic,
+        p_value: adf_p_value(statistic, regression),
+        critical_values,
+        lags: k,
+        nobs,
+    })
+}
+
+/// Approximate asymptotic p-value of the ADF statistic (MacKinnon, 1994), as a normal
+/// distribution function of a polynomial in the statistic.
+fn adf_p_value(statistic: f64, regression: Regression) -> f64 {
+    // (tau_max, tau_min, tau_star, small p coefficients, large p coefficients)
+    let (tau_max, tau_min, tau_star, small, large): (f64, f64, f64, [f64; 3], [f64; 4]) =
+        match regression {
+            Regression::NoConstant => (
+                f64::INFINITY,
+                -19.04,
+                -1.04,
+                [0.6344, 1.2378, 0.032496],
+                [0.4797, 0.93557, -0.06999, 0.033066],
+            ),
+            Regression::Constant => (
+                2.74,
+                -18.83,
+                -1.61,
+                [2.1659, 1.4412, 0.038269],
+                [1.7339, 0.93202, -0.12745, -0.010368],
+            ),
+            Regression::ConstantTrend => (
+                0.7,
+                -16.18,
+                -2.89,
+                [3.2512, 1.6047, 0.049588],
+                [2.5261, 0.61654, -0.37956, -0.060285],
+            ),
+        };
+    if statistic > tau_max {
+        return 1.0;
+    }
+    if statistic < tau_min {
+        return 0.0;
+    }
+    let coef: &[f64] = if statistic <= tau_star {
+        &small
+    } else {
+        &large
+    };
+    let z = coef.iter().rev().fold(0.0, |acc, c| acc * statistic + c);
+    stats::normal_cdf(z)
+}
+
+/// KPSS test for (level or trend) stationarity (Kwiatkowski et al., 1992). The statistic is
+/// `sum_t S_t^2 / (n^2 * s^2)`, where `S_t` are the partial sums of the residuals of the
+/// regression on the deterministic terms and `s^2` is the Newey-West estimate of their
+/// long-run variance. The null hypothesis of stationarity is rejected for large values.
+/// The p-value is interpolated in the table of critical values and therefore lies
+/// between 0.01 and 0.1.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `regression` - `Regression::Constant` for level or `Regression::ConstantTrend` for
+///   trend stationarity.
+/// * `lags` - Bandwidth of the long-run variance estimate. Defaults to
+///   `4*(n/100)^(1/4)`.
+///
+/// # Returns
+///
+/// * Test statistic, p-value and critical values at the 1%, 2.5%, 5% and 10% levels.
+///
+/// # Example
+///
+/// ```
+/// use arima::unitroot::{self, Regression};
+/// let x: Vec<f64> = (0..100).map(|t| t as f64 + ((t * 7919) % 113) as f64).collect();
+/// let result = unitroot::kpss(&x, Regression::Constant, None).unwrap();
+/// assert!(result.p_value < 0.05);
+/// ```
+pub fn kpss(x: &[f64], regression: Regression, lags: Option<usize>) -> Result<TestResult> {
+    let n = x.len();
+    if n < 4 {
+        anyhow::bail!("Time series is too short");
+    }
+    let (residuals, table) = match regression {
+        Regression::NoConstant => anyhow::bail!("The KPSS test needs a constant"),
+        Regression::Constant => {
+            let mean = util::mean(x);
+            (
+                x.iter().map(|v| v - mean).collect::<Vec<f64>>(),
+                [0.739, 0.574, 0.463, 0.347],
+            )
+        }
+        Regression::ConstantTrend => {
+            let columns = vec![vec![1.0; n], (1..=n).map(|t| t as f64).collect()];
+            let coef = linalg::lstsq(&columns, x)?;
+            (
+                (0..n)
+                    .map(|t| x[t] - coef[0] - coef[1] * (t + 1) as f64)
+                    .collect(),
+                [0.216, 0.176, 0.146, 0.119],
+            )
+        }
+    };
+    let lags = lags.unwrap_or((4.0 * (n as f64 / 100.0).powf(0.25)) as usize);
+    if lags >= n {
+        anyhow::bail!("Too many lags for the length of the time series");
+    }
+
+    // Newey-West estimate of the long-run variance with Bartlett weights
+    let autocov = |k: usize| -> f64 {
+        (k..n).map(|t| residuals[t] * residuals[t - k]).sum::<f64>() / n as f64
+    };
+    let mut s2 = autocov(0);
+    for k in 1..=lags {
+        s2 += 2.0 * (1.0 - k as f64 / (lags + 1) as f64) * autocov(k);
+    }
+
+    let mut partial_sum = 0.0;
+    let mut eta = 0.0;
+    for r in &residuals {
+        partial_sum += r;
+        eta += partial_sum * partial_sum;
+    }
+    let statistic = eta / ((n * n) as f64 * s2);
+
+    let levels = [0.01, 0.025, 0.05, 0.1];
+    let critical_values: Vec<(f64, f64)> = levels.iter().copied().zip(table).collect();
+
+    // interpolate linearly between the tabulated levels, with the critical values decreasing
+    let p_value = if statistic >= table[0] {
+        levels[0]
+    } else if statistic <= table[3] {
+        levels[3]
+    } else {
+        let i = (1..4).find(|&i| statistic >= table[i]).unwrap();
+        let w = (statistic - table[i]) / (table[i - 1] - table[i]);
+        levels[i] + w * (levels[i - 1] - levels[i])
+    };
+
+    Ok(TestResult {
+        statistic,
+        p_value,
+        critical_values,
+        lags,
+        nobs: n,
+    })
+}
+
+/// Estimate the number of differences needed to make the series stationary. The series is
+/// differenced until the unit root test does not indicate a unit root at the given level,
+/// i.e. until the KPSS test does not reject stationarity or the ADF test rejects a unit
+/// root. Both tests use a constant in the test regression.
+///
+/// # Arguments
+///
+/// * `&x` - Vector of the timeseries.
+/// * `test` - Unit root test to use.
+/// * `alpha` - Significance level of the tests. Should be between 0.01 and 0.1.
+/// * `max_d` - Maximum number of differences.
+///
+/// # Returns
+///
+/// * Order of differencing `d <= max_d`.
+///
+/// # Example
+///
+/// ```
+/// use arima::unitroot::{self, UnitRootTest};
+/// let x: Vec<f64> = (0..100).map(|t| t as f64 + ((t * 7919) % 113) as f64).collect();
+/// assert_eq!(unitroot::ndiffs(&x, UnitRootTest::Kpss, 0.05, 2).unwrap(), 1);
+/// ```
+pub fn ndiffs(x: &[f64], test: UnitRootTest, alpha: f64, max_d: usize) -> Result<usize> {
+    let mut x = x.to_vec();
+    for d in 0..max_d {
+        // a constant series is stationary
+        if x.iter().all(|v| *v == x[0]) {
+            return Ok(d);
+        }
+        let stationary = match test {
+            UnitRootTest::Kpss => kpss(&x, Regression::Constant, None)?.p_value >= alpha,
+            UnitRootTest::Adf => adf(&x, Regression::Constant, LagSelection::Aic)?.p_value < alpha,
+        };
+        if stationary {
+            return Ok(d);
+        }
+        x = util::diff(&x, 1);
+    }
+    Ok(max_d)
+}
@@ -1,3 +1,4 @@
+use anyhow::Result;
 use core::ops::{Neg, Sub};
 use num::{Float, Num};
 use std::ops::{Add, AddAssign};
@@ -60,6 +61,33 @@ pub fn diff<T: Num + Copy + Neg<Output = T> + Sub>(x: &[T], d: usize) -> Vec<T>
     y
 }
 
+/// Returns a n-d*s vector containing the seasonal difference x_t - x_t-s, applied d times.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `d` - Number of seasonal differences to be taken.
+/// * `period` - Seasonal period s.
+///
+/// # Returns
+///
+/// * Output vector of length n-d*s.
+///
+/// # Example
+///
+/// ```
+/// use arima::util;
+/// let x = [1, 2, 3, 5, 7, 9];
+/// assert_eq!(util::diff_seasonal(&x, 1, 3), &[4, 5, 6])
+/// ```
+pub fn diff_seasonal<T: Num + Copy + Sub>(x: &[T], d: usize, period: usize) -> Vec<T> {
+    let mut y: Vec<T> = x.to_vec();
+    for _ in 0..d {
+        y = (period..y.len()).map(|t| y[t] - y[t - period]).collect();
+    }
+    y
+}
+
 /// Returns a n-1 vector containing the pairwise difference of log(x_t) - log(x_t-1).
 ///
 /// # Arguments
@@ -158,6 +186,185 @@ pub fn diffinv<T: Num + Add + AddAssign + Copy + From<u8>>(x: &[T], d: usize) ->
     cum
 }
 
+/// Calculate the inverse seasonal difference of a vector.
+///
+/// # Arguments
+///
+/// * `&x` - Reference to input vector slice of length n.
+/// * `d` - How often the inverse seasonal differences should be applied.
+/// * `period` - Seasonal period s.
+///
+/// # Returns
+///
+/// * Output vector of length n+d*s containing the inversed values. The first d*s values are zero.
+///
+/// # Example
+///
+/// ```
+/// use arima::util;
+/// let x = [4, 5, 6];
+/// let y = util::diffinv_seasonal(&x, 1, 3);
+/// assert_eq!(y, &[0, 0, 0, 4, 5, 6]);
+///
+/// let z = util::diff_seasonal(&y, 1, 3);
+/// assert_eq!(z, x);
+/// ```
+pub fn diffinv_seasonal<T: Num + Add + AddAssign + Copy + From<u8>>(
+    x: &[T],
+    d: usize,
+    period: usize,
+) -> Vec<T> {
+    let zero = From::from(0);
+    let mut y: Vec<T> = x.to_vec();
+    for _ in 0..d {
+        let mut cum: Vec<T> = [&vec![zero; period], &y[..]].concat();
+        for t in period..cum.len() {
+            let prev = cum[t - period];
+            cum[t] += prev;
+        }
+        y = cum;
+    }
+    y
+}
+
+/// Integrate differenced values, continuing from the end of the undifferenced history.
+/// This undoes `diff` and `diff_seasonal` for values that follow the differenced history,
+/// e.g. forecasts of the differenced series.
+///
+/// # Arguments
+///
+/// * `&history` - Observed undifferenced series, of length at least d+D*s.
+/// * `&w` - Values of the differenced series following the history.
+/// * `d` - Number of differences.
+/// * `seasonal_d` - Number of seasonal differences D.
+/// * `period` - Seasonal period s.
+///
+/// # Returns
+///
+/// * Output vector of the same length as `w` containing the undifferenced values.
+///   Fails if the history is shorter than d+D*s.
+///
+/// # Example
+///
+/// ```
+/// use arima::util;
+/// let x = [1.0, 2.0, 4.0, 7.0];
+/// let y = util::integrate(&x, &[4.0, 5.0], 2, 0, 0).unwrap();
+/// assert_eq!(y, &[14.0, 26.0]);
+/// ```
+pub fn integrate(
+    history: &[f64],
+    w: &[f64],
+    d: usize,
+    seasonal_d: usize,
+    period: usize,
+) -> Result<Vec<f64>> {
+    // x_t = w_t - sum_k delta_k * x_t-k, where delta are the coefficients of
+    // (1 - B)^d (1 - B^s)^D
+    let delta = differencing_polynomial(d, seasonal_d, period);
+    if history.len() < delta.len() - 1 {
+        anyhow::bail!("History is too short for the order of differencing");
+    }
+
+    let mut x = history.to_vec();
+    let n_obs = x.len();
+    for (i, wt) in w.iter().enumerate() {
+        let mut xt = *wt;
+        for k in 1..delta.len() {
+            xt -= delta[k] * x[n_obs + i - k];
+        }
+        x.push(xt);
+    }
+    Ok(x.split_off(n_obs))
+}
+
+/// Coefficients of the differencing polynomial `(1 - B)^d (1 - B^s)^D`.
+// `std::iter::repeat_n` needs Rust 1.82
+#[allow(clippy::manual_repeat_n)]
+pub(crate) fn differencing_polynomial(d: usize, seasonal_d: usize, period: usize) -> Vec<f64> {
+    let mut delta = vec![1.0];
+    let factors = std::iter::repeat(1)
+        .take(d)
+        .chain(std::iter::repeat(period).take(seasonal_d));
+    for lag in factors {
+        let mut next = delta.clone();
+        next.resize(delta.len() + lag, 0.0);
+        for k in 0..delta.len() {
+            next[k + lag] -= delta[k];
+        }
+        delta = next;
+    }
+    delta
+}
+
+/// Expand the multiplicative seasonal AR polynomial
+/// `(1 - phi_1 B - ... - phi_p B^p)(1 - Phi_1 B^s - ... - Phi_P B^Ps)` into the AR
+/// coefficients of a single polynomial of order p+P*s.
+///
+/// # Arguments
+///
+/// * `&phi` - Non-seasonal AR coefficients.
+/// * `&sphi` - Seasonal AR coefficients.
+/// * `period` - Seasonal period s.
+///
+/// # Returns
+///
+/// * Output vector of length p+P*s containing the expanded AR coefficients.
+///
+/// # Example
+///
+/// ```
+/// use arima::util;
+/// let phi = util::expand_seasonal_ar(&[0.5], &[0.2], 4);
+/// assert_eq!(phi, &[0.5, 0.0, 0.0, 0.2, -0.1]);
+/// ```
+pub fn expand_seasonal_ar<T: Num + Copy>(phi: &[T], sphi: &[T], period: usize) -> Vec<T> {
+    let mut y: Vec<T> = vec![T::zero(); phi.len() + sphi.len() * period];
+    y[..phi.len()].copy_from_slice(phi);
+    for (j, &sp) in sphi.iter().enumerate() {
+        let lag = (j + 1) * period;
+        y[lag - 1] = y[lag - 1] + sp;
+        for (i, &p) in phi.iter().enumerate() {
+            y[lag + i] = y[lag + i] - p * sp;
+        }
+    }
+    y
+}
+
+/// Expand the multiplicative seasonal MA polynomial
+/// `(1 + theta_1 B + ... + theta_q B^q)(1 + Theta_1 B^s + ... + Theta_Q B^Qs)` into the MA
+/// coefficients of a single polynomial of order q+Q*s.
+///
+/// # Arguments
+///
+/// * `&theta` - Non-seasonal MA coefficients.
+/// * `&stheta` - Seasonal MA coefficients.
+/// * `period` - Seasonal period s.
+///
+/// # Returns
+///
+/// * Output vector of length q+Q*s containing the expanded MA coefficients.
+///
+/// # Example
+///
+/// ```
+/// use arima::util;
+/// let theta = util::expand_seasonal_ma(&[0.5], &[0.2], 4);
+/// assert_eq!(theta, &[0.5, 0.0, 0.0, 0.2, 0.1]);
+/// ```
+pub fn expand_seasonal_ma<T: Num + Copy>(theta: &[T], stheta: &[T], period: usize) -> Vec<T> {
+    let mut y: Vec<T> = vec![T::zero(); theta.len() + stheta.len() * period];
+    y[..theta.len()].copy_from_slice(theta);
+    for (j, &st) in stheta.iter().enumerate() {
+        let lag = (j + 1) * period;
+        y[lag - 1] = y[lag - 1] + st;
+        for (i, &t) in theta.iter().enumerate() {
+            y[lag + i] = y[lag + i] + t * st;
+        }
+    }
+    y
+}
+
 /// Calculate the mean of a vector.
 ///
 /// # Arguments
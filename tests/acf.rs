@@ -151,6 +151,201 @@ mod test_acf {
         }
     }
 
+    #[test]
+    fn acf_fft_f64() {
+        let x = AR3;
+        let cov_calc = arima::acf::acf_fft(&x, None, true).unwrap();
+        let cor_calc = arima::acf::acf_fft(&x, Some(4), false).unwrap();
+
+        assert_eq!(cov_calc.len(), AR3_COV.len());
+        assert_eq!(cor_calc.len(), 5);
+
+        for i in 0..AR3_COV.len() {
+            assert_lt!((AR3_COV[i] - cov_calc[i]).abs(), 1.0e-7);
+        }
+        for i in 0..cor_calc.len() {
+            assert_lt!((AR3_COR[i] - cor_calc[i]).abs(), 1.0e-7);
+        }
+    }
+
+    #[test]
+    fn acf_fft_long_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(5.0, 2.0).unwrap();
+        let x = arima::sim::arima_sim(
+            5000,
+            Some(&[0.8, -0.5, 0.3]),
+            Some(&[0.4]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // direct sums for comparison
+        let n = x.len();
+        let mean = x.iter().sum::<f64>() / n as f64;
+        let max_lag = 200;
+        let cov_direct: Vec<f64> = (0..=max_lag)
+            .map(|k| {
+                (0..n - k)
+                    .map(|i| (x[i] - mean) * (x[i + k] - mean))
+                    .sum::<f64>()
+                    / n as f64
+            })
+            .collect();
+
+        // acf chooses the FFT for this many lags
+        let cov_auto = arima::acf::acf(&x, Some(max_lag), true).unwrap();
+        let cov_fft = arima::acf::acf_fft(&x, Some(max_lag), true).unwrap();
+        let cor_auto = arima::acf::acf(&x, Some(max_lag), false).unwrap();
+        assert_eq!(cov_auto.len(), max_lag + 1);
+        for k in 0..=max_lag {
+            assert_lt!((cov_auto[k] - cov_direct[k]).abs(), 1.0e-10 * cov_direct[0]);
+            assert_lt!((cov_fft[k] - cov_direct[k]).abs(), 1.0e-10 * cov_direct[0]);
+            assert_lt!((cor_auto[k] - cov_direct[k] / cov_direct[0]).abs(), 1.0e-10);
+        }
+
+        // both methods agree for short lag ranges as well
+        let cor_short = arima::acf::acf(&x, Some(3), false).unwrap();
+        let cor_short_fft = arima::acf::acf_fft(&x, Some(3), false).unwrap();
+        for (a, b) in cor_short.iter().zip(&cor_short_fft) {
+            assert_lt!((a - b).abs(), 1.0e-10);
+        }
+    }
+
+    #[test]
+    fn ccf_f64() {
+        // first and second half of AR3 as two series
+        let x = &AR3[..10];
+        let y = &AR3[10..];
+        let ccf_real = [
+            0.2154544424,
+            0.3353043671,
+            -0.0534134426,
+            -0.0894127830,
+            0.1581691133,
+            0.4494533665,
+            0.2113091773,
+        ];
+        let ccf_calc = arima::acf::ccf(x, y, Some(3), false, None).unwrap();
+        assert_eq!(ccf_calc.len(), ccf_real.len());
+        for (a, b) in ccf_calc.iter().zip(&ccf_real) {
+            assert_lt!((a - b).abs(), 1.0e-7);
+        }
+        let cov_calc = arima::acf::ccf(x, y, Some(0), true, None).unwrap();
+        assert_lt!((cov_calc[0] - -149.4584009457).abs(), 1.0e-7);
+
+        // swapping the series mirrors the lags
+        let ccf_swap = arima::acf::ccf(y, x, None, false, None).unwrap();
+        let ccf_full = arima::acf::ccf(x, y, None, false, None).unwrap();
+        assert_eq!(ccf_full.len(), 19);
+        for (a, b) in ccf_full.iter().zip(ccf_swap.iter().rev()) {
+            assert_lt!((a - b).abs(), 1.0e-12);
+        }
+
+        // the CCF of a series with itself is its ACF on both sides
+        let acf = arima::acf::acf(x, Some(4), false).unwrap();
+        let ccf_self = arima::acf::ccf(x, x, Some(4), false, None).unwrap();
+        for k in 0..=4 {
+            assert_lt!((ccf_self[4 + k] - acf[k]).abs(), 1.0e-12);
+            assert_lt!((ccf_self[4 - k] - acf[k]).abs(), 1.0e-12);
+        }
+
+        assert!(arima::acf::ccf(x, &AR3, None, false, None).is_err());
+    }
+
+    #[test]
+    fn ccf_prewhiten_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let n = 2000;
+        let delay = 3;
+        let x = arima::sim::arima_sim(
+            n + delay,
+            Some(&[0.9]),
+            None,
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+        // y follows x with a delay, plus some noise
+        let y: Vec<f64> = (0..n)
+            .map(|t| 2.0 * x[t] + normal.sample(&mut rng))
+            .collect();
+        let x = &x[delay..];
+
+        let max_lag = 10;
+        let raw = arima::acf::ccf(x, &y, Some(max_lag), false, None).unwrap();
+        let white = arima::acf::ccf(x, &y, Some(max_lag), false, Some(1)).unwrap();
+        assert_eq!(white.len(), 2 * max_lag + 1);
+
+        // both peak at lag -delay, but only the prewhitened CCF vanishes elsewhere
+        let peak = max_lag - delay;
+        let bound = 3.0 / (n as f64).sqrt();
+        assert_lt!(0.5, white[peak]);
+        for (k, (r, w)) in raw.iter().zip(&white).enumerate() {
+            if k != peak {
+                assert_lt!(w.abs(), bound);
+                assert_lt!(*r, raw[peak]);
+            }
+        }
+        assert_lt!(bound, raw[peak - 1]);
+        assert_lt!(bound, raw[peak + 1]);
+    }
+
+    #[test]
+    fn acf_confint_f64() {
+        const LAG: usize = 4;
+        // half-widths of Bartlett's intervals for alpha = 0.05 and alpha = 0.2
+        let widths = [
+            (
+                0.05,
+                [0.0, 0.4382612703, 0.4915786595, 0.4942566989, 0.4992230560],
+            ),
+            (
+                0.2,
+                [0.0, 0.2865636417, 0.3214260087, 0.3231770845, 0.3264244108],
+            ),
+        ];
+        for (alpha, width) in widths {
+            let (acf_calc, confint) = arima::acf::acf_with_confint(&AR3, Some(LAG), alpha).unwrap();
+            assert_eq!(acf_calc.len(), LAG + 1);
+            assert_eq!(confint.len(), LAG + 1);
+            for i in 0..=LAG {
+                assert_lt!((acf_calc[i] - AR3_COR[i]).abs(), 1.0e-7);
+                assert_lt!((confint[i].0 - (AR3_COR[i] - width[i])).abs(), 1.0e-7);
+                assert_lt!((confint[i].1 - (AR3_COR[i] + width[i])).abs(), 1.0e-7);
+            }
+        }
+
+        assert!(arima::acf::acf_with_confint(&AR3, None, 0.0).is_err());
+        assert!(arima::acf::acf_with_confint(&AR3, None, 1.0).is_err());
+    }
+
+    #[test]
+    fn pacf_confint_f64() {
+        const LAG: usize = 4;
+        for (alpha, width) in [(0.05, 0.4382612703), (0.2, 0.2865636417)] {
+            let (pacf_calc, confint) =
+                arima::acf::pacf_with_confint(&AR3, Some(LAG), alpha).unwrap();
+            assert_eq!(pacf_calc.len(), LAG);
+            for i in 0..LAG {
+                assert_lt!((pacf_calc[i] - AR3_PACF[i]).abs(), 1.0e-7);
+                assert_lt!((confint[i].0 - (AR3_PACF[i] - width)).abs(), 1.0e-7);
+                assert_lt!((confint[i].1 - (AR3_PACF[i] + width)).abs(), 1.0e-7);
+            }
+        }
+        assert!(arima::acf::pacf_with_confint(&AR3, None, f64::NAN).is_err());
+    }
+
     #[test]
     fn ar_coef_full_f64() {
         let x = AR3;
@@ -307,4 +502,76 @@ mod test_acf {
             assert_lt!((pacf_real[i] - pacf_calc[i] as f64).abs(), 1.0e-7);
         }
     }
+
+    #[test]
+    fn innovations_ma_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            5000,
+            None,
+            Some(&[0.6, 0.3]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        let (theta, var) = arima::acf::ma(&x, 2, None).unwrap();
+        assert_eq!(theta.len(), 2);
+        assert_lt!((theta[0] - 0.6).abs(), 0.05);
+        assert_lt!((theta[1] - 0.3).abs(), 0.05);
+        assert_lt!((var - 1.0).abs(), 0.05);
+
+        assert!(arima::acf::ma(&x, 3, Some(2)).is_err());
+        assert!(arima::acf::ma(&x[..2], 2, None).is_err());
+    }
+
+    #[test]
+    fn innovations_loglik_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let phi = [0.5];
+        let theta = [0.4];
+        let x = arima::sim::arima_sim(
+            200,
+            Some(&phi),
+            Some(&theta),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // the exact likelihood from the innovations matches the Kalman filter
+        let n = x.len();
+        let cov = arima::arma::theoretical_acf(&phi, &theta, n, true).unwrap();
+        let (pred, v) = arima::acf::innovations_predict(&x, &cov).unwrap();
+        assert_eq!(pred.len(), n + 1);
+        assert_eq!(v.len(), n + 1);
+        let ssq: f64 = (0..n).map(|t| (x[t] - pred[t]).powi(2) / v[t]).sum();
+        let sumlog: f64 = v[..n].iter().map(|vt| vt.ln()).sum();
+        let sigma2 = ssq / n as f64;
+        let loglik =
+            -0.5 * (n as f64 * ((2.0 * std::f64::consts::PI * sigma2).ln() + 1.0) + sumlog);
+        let lik = arima::kalman::arma_loglik(&x, &phi, &theta).unwrap();
+        assert_lt!((sigma2 - lik.sigma2()).abs(), 1.0e-8);
+        assert_lt!((loglik - lik.loglik()).abs(), 1.0e-8);
+
+        // the coefficients converge to the psi-weights
+        let (coef, _v) = arima::acf::innovations_cov(&cov, Some(50)).unwrap();
+        let psi = arima::arma::psi_weights(&phi, &theta, 3);
+        for (a, b) in coef[49].iter().zip(&psi) {
+            assert_lt!((a - b).abs(), 1.0e-6);
+        }
+
+        assert!(arima::acf::innovations_predict(&x, &cov[..n]).is_err());
+        assert!(arima::acf::innovations_cov(&[1.0, 2.0, 0.0], None).is_err());
+    }
 }
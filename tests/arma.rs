@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod test_arma {
+    use more_asserts::assert_lt;
+
+    #[test]
+    fn psi_weights_f64() {
+        // ARMAtoMA(ar = c(1.0, -0.25), ma = 1.0, lag.max = 10) in R
+        let psi = arima::arma::psi_weights(&[1.0, -0.25], &[1.0], 10);
+        let psi_real = [
+            2.0,
+            1.75,
+            1.25,
+            0.8125,
+            0.5,
+            0.296875,
+            0.171875,
+            0.09765625,
+            0.0546875,
+            0.0302734375,
+        ];
+        assert_eq!(psi.len(), 10);
+        for (a, b) in psi.iter().zip(&psi_real) {
+            assert_lt!((a - b).abs(), 1.0e-12);
+        }
+
+        // the psi-weights of a pure MA process are its coefficients
+        let psi = arima::arma::psi_weights(&[], &[0.4, -0.2], 4);
+        assert_eq!(psi, [0.4, -0.2, 0.0, 0.0]);
+        assert!(arima::arma::psi_weights(&[0.5], &[], 0).is_empty());
+    }
+
+    #[test]
+    fn pi_weights_f64() {
+        let phi = [0.6, -0.3];
+        let theta = [0.4, 0.25];
+        let k = 20;
+        let psi = arima::arma::psi_weights(&phi, &theta, k);
+        let pi = arima::arma::pi_weights(&phi, &theta, k);
+
+        // the AR and MA representations are inverse to each other:
+        // (1 - pi_1 B - pi_2 B^2 - ...)(1 + psi_1 B + psi_2 B^2 + ...) = 1
+        let mut psi0 = vec![1.0];
+        psi0.extend(&psi);
+        let mut pi0 = vec![1.0];
+        pi0.extend(pi.iter().map(|p| -p));
+        for h in 1..=k {
+            let c: f64 = (0..=h).map(|j| pi0[j] * psi0[h - j]).sum();
+            assert_lt!(c.abs(), 1.0e-12);
+        }
+
+        // the pi-weights of a pure AR process are its coefficients
+        let pi = arima::arma::pi_weights(&phi, &[], 4);
+        assert_eq!(pi, [0.6, -0.3, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn psi_weights_sarima_f64() {
+        let x = [1.0, 5.0, 3.0, 2.0, 6.0, 4.0, 3.0, 7.0, 5.0, 3.5, 7.5, 5.0];
+        let options = arima::estimate::FitOptions::new().with_seasonal(1, 0, 0, 3);
+        let model = arima::estimate::fit_with_options(&x, 0, 0, 1, &options).unwrap();
+
+        // (1 - Phi B^3) x_t = (1 + theta B) e_t
+        let theta = model.theta()[0];
+        let sphi = model.seasonal_phi()[0];
+        let psi = model.psi_weights(7);
+        let psi_real = [
+            theta,
+            0.0,
+            sphi,
+            sphi * theta,
+            0.0,
+            sphi * sphi,
+            sphi * sphi * theta,
+        ];
+        for (a, b) in psi.iter().zip(&psi_real) {
+            assert_lt!((a - b).abs(), 1.0e-12);
+        }
+        assert_eq!(model.pi_weights(7).len(), 7);
+    }
+
+    #[test]
+    fn theoretical_acf_f64() {
+        // AR(2): rho_1 = phi_1 / (1 - phi_2), then rho_k = phi_1 rho_k-1 + phi_2 rho_k-2
+        let rho = arima::arma::theoretical_acf(&[1.0, -0.25], &[], 4, false).unwrap();
+        let rho_real = [1.0, 0.8, 0.55, 0.35, 0.2125];
+        for (a, b) in rho.iter().zip(&rho_real) {
+            assert_lt!((a - b).abs(), 1.0e-12);
+        }
+
+        // MA(1): only the first lag is correlated
+        let theta = 0.4;
+        let gamma = arima::arma::theoretical_acf(&[], &[theta], 3, true).unwrap();
+        assert_lt!((gamma[0] - (1.0 + theta * theta)).abs(), 1.0e-12);
+        assert_lt!((gamma[1] - theta).abs(), 1.0e-12);
+        assert_eq!(gamma[2..], [0.0, 0.0]);
+
+        // ARMA(1,1)
+        let (phi, theta) = (0.6, 0.3);
+        let gamma = arima::arma::theoretical_acf(&[phi], &[theta], 2, true).unwrap();
+        let gamma0 = (1.0 + 2.0 * phi * theta + theta * theta) / (1.0 - phi * phi);
+        let gamma1 = (1.0 + phi * theta) * (phi + theta) / (1.0 - phi * phi);
+        assert_lt!((gamma[0] - gamma0).abs(), 1.0e-12);
+        assert_lt!((gamma[1] - gamma1).abs(), 1.0e-12);
+        assert_lt!((gamma[2] - phi * gamma1).abs(), 1.0e-12);
+
+        assert!(arima::arma::theoretical_acf(&[1.0], &[], 3, false).is_err());
+    }
+
+    #[test]
+    fn theoretical_acf_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let phi = [0.6, -0.2];
+        let theta = [0.3];
+        let x = arima::sim::arima_sim(
+            20000,
+            Some(&phi),
+            Some(&theta),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // the sample statistics of a long simulation match the theoretical ones
+        let rho = arima::arma::theoretical_acf(&phi, &theta, 5, false).unwrap();
+        let rho_sample = arima::acf::acf(&x, Some(5), false).unwrap();
+        for (a, b) in rho.iter().zip(&rho_sample) {
+            assert_lt!((a - b).abs(), 0.03);
+        }
+        let pacf = arima::arma::theoretical_pacf(&phi, &theta, 5).unwrap();
+        let pacf_sample = arima::acf::pacf(&x, Some(5)).unwrap();
+        assert_eq!(pacf.len(), 5);
+        for (a, b) in pacf.iter().zip(&pacf_sample) {
+            assert_lt!((a - b).abs(), 0.03);
+        }
+
+        // the PACF of an MA(1) process is -(-theta)^k (1 - theta^2) / (1 - theta^(2k+2))
+        let theta = 0.5_f64;
+        let pacf = arima::arma::theoretical_pacf(&[], &[theta], 3).unwrap();
+        for (k, p) in pacf.iter().enumerate() {
+            let k = k as i32 + 1;
+            let real = -(-theta).powi(k) * (1.0 - theta * theta) / (1.0 - theta.powi(2 * k + 2));
+            assert_lt!((p - real).abs(), 1.0e-12);
+        }
+    }
+}
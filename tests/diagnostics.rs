@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test_diagnostics {
+    use more_asserts::assert_lt;
+    use rand::prelude::*;
+    use rand_distr::{Distribution, Normal};
+
+    fn arma(n: usize, phi: &[f64], theta: &[f64]) -> Vec<f64> {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        arima::sim::arima_sim(
+            n,
+            Some(phi),
+            Some(theta),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn portmanteau_f64() {
+        let x = [1.0, 3.0, 2.0, 5.0, 4.0, 3.0, 6.0, 2.0, 4.0, 5.0, 3.0, 1.0];
+        let n = x.len() as f64;
+        let rho = arima::acf::acf(&x, Some(3), false).unwrap();
+
+        let lb = arima::diagnostics::ljung_box(&x, 3, 1).unwrap();
+        let q: f64 = (1..=3).map(|k| rho[k] * rho[k] / (n - k as f64)).sum();
+        assert_lt!((lb.statistic - n * (n + 2.0) * q).abs(), 1.0e-12);
+        assert_eq!(lb.df, 2);
+        assert_eq!(lb.lags, 3);
+        assert_lt!((lb.p_value - (-0.5 * lb.statistic).exp()).abs(), 1.0e-12);
+
+        let bp = arima::diagnostics::box_pierce(&x, 3, 0).unwrap();
+        let q: f64 = (1..=3).map(|k| rho[k] * rho[k]).sum();
+        assert_lt!((bp.statistic - n * q).abs(), 1.0e-12);
+        assert_eq!(bp.df, 3);
+        assert_lt!(bp.statistic, lb.statistic);
+
+        assert!(arima::diagnostics::ljung_box(&x, 0, 0).is_err());
+        assert!(arima::diagnostics::ljung_box(&x, 3, 3).is_err());
+        assert!(arima::diagnostics::ljung_box(&x, 12, 0).is_err());
+    }
+
+    #[test]
+    fn diagnostics_sim() {
+        let x = arma(1000, &[0.7], &[0.4]);
+
+        // the residuals of an underfitted model are autocorrelated
+        let model = arima::estimate::fit(&x, 1, 0, 0).unwrap();
+        let diagnostics = model.diagnostics(None).unwrap();
+        assert_lt!(diagnostics.ljung_box.p_value, 0.01);
+        assert_lt!(diagnostics.box_pierce.p_value, 0.01);
+
+        // the residuals of the true model are white noise
+        let model = arima::estimate::fit(&x, 1, 0, 1).unwrap();
+        let diagnostics = model.diagnostics(None).unwrap();
+        assert_eq!(diagnostics.ljung_box.lags, 10);
+        assert_eq!(diagnostics.ljung_box.df, 8);
+        assert_eq!(diagnostics.residual_acf.len(), 11);
+        assert_lt!(0.05, diagnostics.ljung_box.p_value);
+        assert_lt!(0.05, diagnostics.box_pierce.p_value);
+
+        let diagnostics = model.diagnostics(Some(20)).unwrap();
+        assert_eq!(diagnostics.ljung_box.df, 18);
+    }
+}
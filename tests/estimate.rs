@@ -112,7 +112,7 @@ mod test_estimate {
     fn fit_arima_2002_f64() {
         let x = AR3;
 
-        let coef = arima::estimate::fit(&x, 2, 0, 0).unwrap();
+        let coef = arima::estimate::fit(&x, 2, 0, 0).unwrap().coef();
 
         // Results obtained from R with
         // `cf <- arima(x, order=c(2, 0, 0), method="CSS", optim.method="L-BFGS-B")$coef`
@@ -126,7 +126,7 @@ mod test_estimate {
     fn fit_arima_101_f64() {
         let x = AR3;
 
-        let coef = arima::estimate::fit(&x, 1, 0, 1).unwrap();
+        let coef = arima::estimate::fit(&x, 1, 0, 1).unwrap().coef();
 
         // Results obtained from R with
         // `cf <- arima(x, order=c(1, 0, 1), method="CSS", optim.method="L-BFGS-B")$coef`
@@ -140,7 +140,7 @@ mod test_estimate {
     fn fit_arima_102_f64() {
         let x = AR3;
 
-        let coef = arima::estimate::fit(&x, 1, 0, 2).unwrap();
+        let coef = arima::estimate::fit(&x, 1, 0, 2).unwrap().coef();
         println!("{:?}", coef);
 
         // Results obtained from R with
@@ -155,4 +155,566 @@ mod test_estimate {
         assert_lt!((coef[2] - 1.0564438).abs(), 1.0e-2); // MA 1
         assert_lt!((coef[3] - 1.5102864).abs(), 1.0e-2); // MA 2
     }
+
+    #[test]
+    fn fit_ml_arima_200_f64() {
+        use arima::estimate::{FitOptions, Method};
+        let x = AR3;
+
+        let ml = arima::estimate::fit_with_options(
+            &x,
+            2,
+            0,
+            0,
+            &FitOptions::new().with_method(Method::Ml),
+        )
+        .unwrap();
+        let css_ml = arima::estimate::fit_with_options(
+            &x,
+            2,
+            0,
+            0,
+            &FitOptions::new().with_method(Method::CssMl),
+        )
+        .unwrap();
+
+        // both methods maximize the same likelihood
+        assert_lt!((ml.loglik() - css_ml.loglik()).abs(), 1.0e-4);
+        assert_lt!((ml.phi()[0] - css_ml.phi()[0]).abs(), 1.0e-3);
+        assert_lt!((ml.phi()[1] - css_ml.phi()[1]).abs(), 1.0e-3);
+        assert_eq!(ml.nobs(), x.len());
+        assert_eq!(ml.residuals().len(), x.len());
+
+        // the exact likelihood at the CSS estimates is lower
+        let css = arima::estimate::fit(&x, 2, 0, 0).unwrap();
+        let mean = css.intercept() / (1.0 - css.phi().iter().sum::<f64>());
+        let y: Vec<f64> = x.iter().map(|v| v - mean).collect();
+        let lik = arima::kalman::arma_loglik(&y, css.phi(), css.theta()).unwrap();
+        assert_lt!(lik.loglik(), ml.loglik());
+    }
+
+    #[test]
+    fn fit_ml_arima_101_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            1000,
+            Some(&[0.6]),
+            Some(&[0.3]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        let model = arima::estimate::fit_with_options(
+            &x,
+            1,
+            0,
+            1,
+            &FitOptions::new().with_method(Method::Ml),
+        )
+        .unwrap();
+
+        assert_lt!((model.phi()[0] - 0.6).abs(), 0.1);
+        assert_lt!((model.theta()[0] - 0.3).abs(), 0.1);
+        assert_lt!((model.sigma2() - 1.0).abs(), 0.1);
+    }
+
+    #[test]
+    fn fit_sarima_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::sarima_sim(
+            600,
+            Some(&[0.5]),
+            None,
+            0,
+            &arima::sim::Seasonal {
+                ar: None,
+                ma: Some(&[0.6]),
+                d: 1,
+                period: 12,
+            },
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        for method in [Method::Css, Method::CssMl] {
+            let options = FitOptions::new()
+                .with_method(method)
+                .with_seasonal(0, 1, 1, 12);
+            let model = arima::estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+
+            assert_eq!(model.seasonal_order().period, 12);
+            assert_eq!(model.phi().len(), 1);
+            assert_eq!(model.seasonal_theta().len(), 1);
+            assert_eq!(model.residuals().len(), x.len() - 12);
+            assert_lt!((model.phi()[0] - 0.5).abs(), 0.1);
+            assert_lt!((model.seasonal_theta()[0] - 0.6).abs(), 0.1);
+        }
+
+        // seasonal orders longer than the series are rejected
+        let options = FitOptions::new().with_seasonal(1, 0, 0, 12);
+        assert!(arima::estimate::fit_with_options(&x[..10], 0, 0, 0, &options).is_err());
+        let options = FitOptions::new().with_seasonal(0, 1, 1, 12);
+        assert!(arima::estimate::fit_with_options(&x[..24], 0, 0, 0, &options).is_err());
+        assert!(arima::estimate::fit_with_options(&x[..5], 0, 0, 0, &options).is_err());
+    }
+
+    #[test]
+    fn fit_xreg_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        let n = 500;
+        let z1: Vec<f64> = (0..n).map(|t| (t as f64 / 7.0).sin() * 10.0).collect();
+        let z2: Vec<f64> = (0..n).map(|t| if t % 5 == 0 { 1.0 } else { 0.0 }).collect();
+
+        for d in [0, 1] {
+            let u = arima::sim::arima_sim(
+                n,
+                Some(&[0.6]),
+                None,
+                d,
+                &|mut rng| normal.sample(&mut rng),
+                &mut rng,
+            )
+            .unwrap();
+            let x: Vec<f64> = (0..n)
+                .map(|t| 100.0 + 3.0 * z1[t] - 1.5 * z2[t] + u[t])
+                .collect();
+
+            for method in [Method::Css, Method::CssMl] {
+                let options = FitOptions::new()
+                    .with_method(method)
+                    .with_xreg(vec![z1.clone(), z2.clone()]);
+                let model = arima::estimate::fit_with_options(&x, 1, d, 0, &options).unwrap();
+
+                assert_eq!(model.xreg_coef().len(), 2);
+                assert_lt!((model.phi()[0] - 0.6).abs(), 0.1);
+                assert_lt!((model.xreg_coef()[0] - 3.0).abs(), 0.2);
+                assert_lt!((model.xreg_coef()[1] + 1.5).abs(), 0.2);
+            }
+        }
+    }
+
+    #[test]
+    fn fit_transform_pars_arima_102_f64() {
+        use arima::estimate::{FitOptions, Method};
+        let x = AR3;
+
+        // the unconstrained CSS estimates are not invertible
+        let model = arima::estimate::fit(&x, 1, 0, 2).unwrap();
+        assert_lt!(model.ma_root_moduli()[0], 1.0);
+
+        for method in [Method::Css, Method::Ml, Method::CssMl] {
+            let options = FitOptions::new()
+                .with_method(method)
+                .with_transform_pars(true);
+            let model = arima::estimate::fit_with_options(&x, 1, 0, 2, &options).unwrap();
+            for m in model.ar_root_moduli() {
+                assert_lt!(1.0, m);
+            }
+            for m in model.ma_root_moduli() {
+                assert_lt!(1.0 - 1.0e-6, m);
+            }
+        }
+    }
+
+    #[test]
+    fn autofit_sim() {
+        use arima::estimate::{AutofitOptions, InformationCriterion};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            500,
+            Some(&[0.7]),
+            None,
+            1,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        let model = arima::estimate::autofit(&x, Some(1)).unwrap();
+        assert_eq!(model.order(), (1, 1, 0));
+
+        // the order of differencing is chosen by a KPSS test
+        let model = arima::estimate::autofit(&x, None).unwrap();
+        assert_eq!(model.order(), (1, 1, 0));
+
+        // series too short for the KPSS test are not differenced. The AICc needs more
+        // observations than coefficients plus one.
+        let options = AutofitOptions::new().with_criterion(InformationCriterion::Aic);
+        let selection = arima::estimate::autofit_with_options(&x[..3], &options).unwrap();
+        assert_eq!(selection.order().1, 0);
+
+        for criterion in [
+            InformationCriterion::Aic,
+            InformationCriterion::Aicc,
+            InformationCriterion::Bic,
+        ] {
+            let options = AutofitOptions::new()
+                .with_d(1)
+                .with_max_order(3, 3)
+                .with_criterion(criterion);
+            let stepwise = arima::estimate::autofit_with_options(&x, &options).unwrap();
+            let grid =
+                arima::estimate::autofit_with_options(&x, &options.with_stepwise(false)).unwrap();
+
+            // the grid search fits all 15 models with p, q <= 3 and p + q <= 5
+            assert_eq!(grid.candidates.len(), 15);
+            assert_lt!(stepwise.candidates.len(), grid.candidates.len());
+
+            // the selected model has the smallest criterion of all candidates
+            let min_ic = |candidates: &[arima::estimate::Candidate]| {
+                candidates.iter().fold(f64::INFINITY, |m, c| m.min(c.ic))
+            };
+            let grid_best = grid
+                .candidates
+                .iter()
+                .find(|c| c.ic == min_ic(&grid.candidates))
+                .unwrap();
+            assert_eq!(grid.order(), grid_best.order);
+            assert!(min_ic(&grid.candidates) <= min_ic(&stepwise.candidates));
+        }
+    }
+
+    #[test]
+    fn fit_gradient_cross_check() {
+        use arima::estimate::{FitOptions, Gradient};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        let n = 600;
+        let seasonal = arima::sim::Seasonal {
+            ar: Some(&[0.4]),
+            ma: Some(&[-0.3]),
+            d: 0,
+            period: 4,
+        };
+        let u = arima::sim::sarima_sim(
+            n,
+            Some(&[0.5]),
+            Some(&[0.3]),
+            0,
+            &seasonal,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+        let z: Vec<f64> = (0..n).map(|t| (t as f64 / 7.0).sin() * 10.0).collect();
+        let x: Vec<f64> = (0..n).map(|t| 5.0 + 2.0 * z[t] + u[t]).collect();
+
+        // the analytic and the finite difference gradient lead to the same estimates
+        for transform_pars in [false, true] {
+            let options = FitOptions::new()
+                .with_seasonal(1, 0, 1, 4)
+                .with_xreg(vec![z.clone()])
+                .with_transform_pars(transform_pars);
+            let analytic = arima::estimate::fit_with_options(
+                &x,
+                1,
+                0,
+                1,
+                &options.clone().with_gradient(Gradient::Analytic),
+            )
+            .unwrap();
+            let forward = arima::estimate::fit_with_options(
+                &x,
+                1,
+                0,
+                1,
+                &options.with_gradient(Gradient::ForwardDiff),
+            )
+            .unwrap();
+            for (a, f) in analytic.coef().iter().zip(forward.coef()) {
+                assert_lt!((a - f).abs(), 1.0e-3);
+            }
+            assert_lt!(analytic.sigma2(), forward.sigma2() + 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn fit_fixed_coefficients_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            1000,
+            Some(&[0.5, 0.0, 0.3]),
+            None,
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        for method in [Method::Css, Method::Ml, Method::CssMl] {
+            let options =
+                FitOptions::new()
+                    .with_method(method)
+                    .with_fixed(vec![None, None, Some(0.0), None]);
+            let model = arima::estimate::fit_with_options(&x, 3, 0, 0, &options).unwrap();
+            assert_eq!(model.phi()[1], 0.0);
+            assert_lt!((model.phi()[0] - 0.5).abs(), 0.1);
+            assert_lt!((model.phi()[2] - 0.3).abs(), 0.1);
+
+            // the fixed coefficient has no standard error and is not counted as parameter
+            let se = model.std_errors();
+            assert!(se[2].is_nan());
+            assert!(se[0].is_finite() && se[1].is_finite() && se[3].is_finite());
+            let n = model.nobs() as f64;
+            assert_lt!(
+                (model.aic() - model.bic() + 4.0 * (n.ln() - 2.0)).abs(),
+                1.0e-9
+            );
+        }
+
+        // without a mean, the intercept is fixed at zero
+        let options = FitOptions::new().with_mean(false);
+        let model = arima::estimate::fit_with_options(&x, 3, 0, 0, &options).unwrap();
+        assert_eq!(model.intercept(), 0.0);
+
+        // the mask must cover all coefficients
+        let options = FitOptions::new().with_fixed(vec![None, Some(0.0)]);
+        assert!(arima::estimate::fit_with_options(&x, 3, 0, 0, &options).is_err());
+    }
+
+    #[test]
+    fn fit_init_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            1000,
+            Some(&[0.6]),
+            Some(&[0.3]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // warm-starting at the previous estimates reproduces them
+        for method in [Method::Css, Method::Ml] {
+            let options = FitOptions::new().with_method(method);
+            let model = arima::estimate::fit_with_options(&x, 1, 0, 1, &options).unwrap();
+            let options = options.with_init(model.coef());
+            let refit = arima::estimate::fit_with_options(&x, 1, 0, 1, &options).unwrap();
+            for (a, b) in model.coef().iter().zip(refit.coef()) {
+                assert_lt!((a - b).abs(), 1.0e-3);
+            }
+        }
+
+        // without iterations, the estimates are the initial values
+        let options = FitOptions::new()
+            .with_init(vec![0.1, 0.2, 0.3])
+            .with_max_iterations(0);
+        let model = arima::estimate::fit_with_options(&x, 1, 0, 1, &options).unwrap();
+        assert_eq!(model.coef(), vec![0.1, 0.2, 0.3]);
+
+        let options = FitOptions::new().with_init(vec![0.1, 0.2]);
+        assert!(arima::estimate::fit_with_options(&x, 1, 0, 1, &options).is_err());
+    }
+
+    #[test]
+    fn fit_convergence_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use arima::model::ConvergenceStatus;
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            500,
+            Some(&[0.6, -0.2]),
+            Some(&[0.3]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        for method in [Method::Css, Method::Ml, Method::CssMl] {
+            let options = FitOptions::new()
+                .with_method(method)
+                .with_require_convergence(true);
+            let model = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+            let convergence = model.convergence();
+            assert!(convergence.converged());
+            assert_lt!(0, convergence.iterations);
+            assert_lt!(convergence.gradient_norm, 1.0e-3);
+        }
+
+        // stopping early is reported, or an error if convergence is required
+        let options = FitOptions::new().with_max_iterations(2);
+        let model = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+        assert_eq!(model.convergence().status, ConvergenceStatus::MaxIterations);
+        assert_eq!(model.convergence().iterations, 2);
+        let options = options.with_require_convergence(true);
+        assert!(arima::estimate::fit_with_options(&x, 2, 0, 1, &options).is_err());
+
+        // a loose tolerance on the objective stops the optimization earlier
+        let model = arima::estimate::fit(&x, 2, 0, 1).unwrap();
+        let options = FitOptions::new().with_function_tolerance(1.0e-3);
+        let loose = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+        assert!(loose.convergence().converged());
+        assert_lt!(
+            loose.convergence().iterations,
+            model.convergence().iterations
+        );
+        assert_lt!(
+            loose.convergence().objective,
+            model.convergence().objective * (1.0 + 1.0e-2)
+        );
+    }
+
+    #[test]
+    fn fit_whittle_sim() {
+        use arima::estimate::{FitOptions, Method};
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(10.0, 2.0).unwrap();
+        let x = arima::sim::arima_sim(
+            5000,
+            Some(&[0.7, -0.2]),
+            Some(&[0.4]),
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // the Whittle estimates are close to the CSS estimates
+        let css = arima::estimate::fit(&x, 2, 0, 1).unwrap();
+        let options = FitOptions::new().with_method(Method::Whittle);
+        let model = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+        assert!(model.convergence().converged());
+        for (a, b) in model.coef().iter().zip(css.coef()) {
+            assert_lt!((a - b).abs(), 0.02 * b.abs().max(1.0));
+        }
+        assert_lt!((model.sigma2() - css.sigma2()).abs(), 0.01 * css.sigma2());
+
+        // the ARMA coefficients have standard errors, the intercept has none
+        let se = model.std_errors();
+        assert!(se[0].is_nan());
+        for (a, b) in se[1..].iter().zip(&css.std_errors()[1..]) {
+            assert_lt!((a - b).abs(), 0.1 * b);
+        }
+
+        // fixed coefficients keep their values
+        let options = options.with_fixed(vec![None, None, Some(-0.2), None]);
+        let model = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+        assert_eq!(model.phi()[1], -0.2);
+        assert_lt!((model.phi()[0] - 0.7).abs(), 0.05);
+        assert!(model.std_errors()[2].is_nan());
+
+        // a non-invertible initial MA part ends in the invertible estimate
+        let options = FitOptions::new()
+            .with_method(Method::Whittle)
+            .with_init(vec![0.0, 0.5, 0.0, 1.0 / 0.6]);
+        let model = arima::estimate::fit_with_options(&x, 2, 0, 1, &options).unwrap();
+        assert!(model.ma_root_moduli().iter().all(|m| *m > 1.0));
+        assert_lt!((model.theta()[0] - css.theta()[0]).abs(), 0.05);
+
+        // seasonal models
+        let x = arima::sim::sarima_sim(
+            2000,
+            Some(&[0.5]),
+            None,
+            0,
+            &arima::sim::Seasonal {
+                ar: None,
+                ma: Some(&[0.6]),
+                d: 0,
+                period: 12,
+            },
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+        let options = FitOptions::new()
+            .with_method(Method::Whittle)
+            .with_seasonal(0, 0, 1, 12);
+        let model = arima::estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+        assert_lt!((model.phi()[0] - 0.5).abs(), 0.05);
+        assert_lt!((model.seasonal_theta()[0] - 0.6).abs(), 0.05);
+    }
+
+    #[test]
+    fn hannan_rissanen_sim() {
+        use arima::estimate::FitOptions;
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            5000,
+            Some(&[0.6]),
+            Some(&[0.4]),
+            1,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        // the estimates are close to the true values and the CSS estimates
+        let model = arima::estimate::hannan_rissanen(&x, 1, 1, 1).unwrap();
+        assert_eq!(model.order(), (1, 1, 1));
+        assert_lt!((model.phi()[0] - 0.6).abs(), 0.05);
+        assert_lt!((model.theta()[0] - 0.4).abs(), 0.05);
+        assert_lt!((model.sigma2() - 1.0).abs(), 0.05);
+        let css = arima::estimate::fit(&x, 1, 1, 1).unwrap();
+        for (a, b) in model.coef().iter().zip(css.coef()) {
+            assert_lt!((a - b).abs(), 0.05);
+        }
+
+        // they are the initial values of fit
+        let options = FitOptions::new().with_max_iterations(0);
+        let init = arima::estimate::fit_with_options(&x, 1, 1, 1, &options).unwrap();
+        for (a, b) in model.coef().iter().zip(init.coef()) {
+            assert_lt!((a - b).abs(), 1.0e-12);
+        }
+
+        // pure AR models are least squares fits
+        let model = arima::estimate::hannan_rissanen(&x, 2, 1, 0).unwrap();
+        let css = arima::estimate::fit(&x, 2, 1, 0).unwrap();
+        for (a, b) in model.phi().iter().zip(css.phi()) {
+            assert_lt!((a - b).abs(), 1.0e-3);
+        }
+
+        assert!(arima::estimate::hannan_rissanen(&x[..5], 1, 1, 1).is_err());
+        assert!(arima::estimate::hannan_rissanen(&[1.0, 2.0, 3.0], 1, 5, 0).is_err());
+    }
 }
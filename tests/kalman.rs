@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod test_kalman {
+    use more_asserts::assert_lt;
+
+    const X: [f64; 8] = [0.3, -1.2, 0.8, 1.5, 0.1, -0.7, -0.2, 0.9];
+
+    /// Exact log-likelihood terms (ssq, sumlog) of a zero-mean Gaussian vector with
+    /// the given autocovariances, via the Cholesky decomposition of the covariance matrix.
+    #[allow(clippy::needless_range_loop)]
+    fn direct_loglik(x: &[f64], acvf: &[f64]) -> (f64, f64) {
+        let n = x.len();
+        let mut l = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..=i {
+                let mut s = acvf[i - j];
+                for k in 0..j {
+                    s -= l[i][k] * l[j][k];
+                }
+                if i == j {
+                    l[i][j] = s.sqrt();
+                } else {
+                    l[i][j] = s / l[j][j];
+                }
+            }
+        }
+        // solve L z = x
+        let mut z = vec![0.0; n];
+        for i in 0..n {
+            let mut s = x[i];
+            for k in 0..i {
+                s -= l[i][k] * z[k];
+            }
+            z[i] = s / l[i][i];
+        }
+        let ssq = z.iter().map(|v| v * v).sum();
+        let sumlog = (0..n).map(|i| 2.0 * l[i][i].ln()).sum();
+        (ssq, sumlog)
+    }
+
+    #[test]
+    fn loglik_ar1() {
+        let phi: f64 = 0.6;
+        let acvf: Vec<f64> = (0..X.len())
+            .map(|k| phi.powi(k as i32) / (1.0 - phi * phi))
+            .collect();
+        let (ssq, sumlog) = direct_loglik(&X, &acvf);
+
+        let lik = arima::kalman::arma_loglik(&X, &[phi], &[]).unwrap();
+
+        assert_eq!(lik.nobs, X.len());
+        assert_lt!((lik.ssq - ssq).abs(), 1.0e-10);
+        assert_lt!((lik.sumlog - sumlog).abs(), 1.0e-10);
+    }
+
+    #[test]
+    fn loglik_ma1() {
+        let theta = 0.7;
+        let mut acvf = vec![0.0; X.len()];
+        acvf[0] = 1.0 + theta * theta;
+        acvf[1] = theta;
+        let (ssq, sumlog) = direct_loglik(&X, &acvf);
+
+        let lik = arima::kalman::arma_loglik(&X, &[], &[theta]).unwrap();
+
+        assert_lt!((lik.ssq - ssq).abs(), 1.0e-10);
+        assert_lt!((lik.sumlog - sumlog).abs(), 1.0e-10);
+    }
+
+    #[test]
+    fn loglik_arma11() {
+        let phi = -0.4;
+        let theta = 0.5;
+        // autocovariances of an ARMA(1,1) process with unit innovation variance
+        let mut acvf = vec![0.0; X.len()];
+        acvf[0] = (1.0 + 2.0 * phi * theta + theta * theta) / (1.0 - phi * phi);
+        acvf[1] = (1.0 + phi * theta) * (phi + theta) / (1.0 - phi * phi);
+        for k in 2..X.len() {
+            acvf[k] = phi * acvf[k - 1];
+        }
+        let (ssq, sumlog) = direct_loglik(&X, &acvf);
+
+        let lik = arima::kalman::arma_loglik(&X, &[phi], &[theta]).unwrap();
+
+        assert_lt!((lik.ssq - ssq).abs(), 1.0e-10);
+        assert_lt!((lik.sumlog - sumlog).abs(), 1.0e-10);
+    }
+
+    #[test]
+    fn loglik_nonstationary() {
+        assert!(arima::kalman::arma_loglik(&X, &[1.0], &[]).is_err());
+        assert!(arima::kalman::arma_loglik(&X, &[0.5, 0.6], &[]).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+#[cfg(test)]
+mod test_model {
+    use more_asserts::assert_lt;
+
+    const AR3: [f64; 20] = [
+        149.8228533548,
+        86.8388399871,
+        42.3116899484,
+        76.6796578536,
+        60.3665347774,
+        66.7733563129,
+        -5.1144504108,
+        14.0294086329,
+        76.2517878809,
+        121.2898170491,
+        74.65663878,
+        69.9331198692,
+        46.7476543397,
+        26.2225173663,
+        -32.0638217183,
+        2.8335240789,
+        31.5182582874,
+        76.4827451823,
+        36.6122657518,
+        -33.430444607,
+    ];
+
+    #[test]
+    fn fitted_arima_101_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 1, 0, 1).unwrap();
+
+        assert_eq!(model.order(), (1, 0, 1));
+        assert_eq!(model.phi().len(), 1);
+        assert_eq!(model.theta().len(), 1);
+        assert_eq!(model.nobs(), 19);
+        assert_eq!(model.coef().len(), 3);
+
+        // residuals and fitted values add up to the observations
+        assert_eq!(model.residuals().len(), x.len());
+        assert_eq!(model.fitted_values().len(), x.len());
+        for (i, xi) in x.iter().enumerate() {
+            let sum = model.residuals()[i] + model.fitted_values()[i];
+            assert_lt!((sum - xi).abs(), 1.0e-7);
+        }
+
+        let css: f64 = model.residuals().iter().map(|r| r * r).sum();
+        assert_lt!((model.sigma2() - css / 19.0).abs(), 1.0e-7);
+
+        assert!(model.summary().starts_with("ARIMA(1,0,1)"));
+    }
+
+    #[test]
+    fn forecast_ar1_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 1, 0, 0).unwrap();
+        let forecast = model.forecast(3).unwrap();
+
+        let c = model.intercept();
+        let phi = model.phi()[0];
+        let f1 = c + phi * x[x.len() - 1];
+        let f2 = c + phi * f1;
+        let f3 = c + phi * f2;
+
+        assert_lt!((forecast[0] - f1).abs(), 1.0e-7);
+        assert_lt!((forecast[1] - f2).abs(), 1.0e-7);
+        assert_lt!((forecast[2] - f3).abs(), 1.0e-7);
+    }
+
+    #[test]
+    fn forecast_arima_010_f64() {
+        let x = [1.0, 2.0, 4.0, 5.0, 7.0];
+        let model = arima::estimate::fit(&x, 0, 1, 0).unwrap();
+
+        // a random walk with drift continues from the last observation
+        assert_lt!((model.intercept() - 1.5).abs(), 1.0e-4);
+        let forecast = model.forecast(2).unwrap();
+        assert_lt!((forecast[0] - 8.5).abs(), 1.0e-4);
+        assert_lt!((forecast[1] - 10.0).abs(), 1.0e-4);
+    }
+
+    #[test]
+    fn forecast_sarima_seasonal_random_walk_f64() {
+        let x = [1.0, 5.0, 3.0, 2.0, 6.0, 4.0, 3.0, 7.0, 5.0];
+        let options = arima::estimate::FitOptions::new().with_seasonal(0, 1, 0, 3);
+        let model = arima::estimate::fit_with_options(&x, 0, 0, 0, &options).unwrap();
+
+        // each season increases by 1.0, continuing from the last season
+        assert_lt!((model.intercept() - 1.0).abs(), 1.0e-4);
+        let forecast = model.forecast(4).unwrap();
+        let forecast_real = [4.0, 8.0, 6.0, 5.0];
+        for i in 0..forecast.len() {
+            assert_lt!((forecast[i] - forecast_real[i]).abs(), 1.0e-4);
+        }
+        assert!(model.summary().starts_with("ARIMA(0,0,0)(0,1,0)[3]"));
+    }
+
+    #[test]
+    fn predict_ar1_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 1, 0, 0).unwrap();
+        let forecast = model.predict(4, &[0.8, 0.95]).unwrap();
+        assert_eq!(forecast.mean, model.forecast(4).unwrap());
+
+        // psi_j = phi^j
+        let phi = model.phi()[0];
+        let mut var = 0.0;
+        for h in 0..4 {
+            var += phi.powi(2 * h as i32);
+            let se = (model.sigma2() * var).sqrt();
+            assert_lt!((forecast.std_errors[h] - se).abs(), 1.0e-7);
+        }
+
+        let z = [1.2815515655446004, 1.959963984540054];
+        for (interval, z) in forecast.intervals.iter().zip(z) {
+            for h in 0..4 {
+                let half_width = z * forecast.std_errors[h];
+                assert_lt!(
+                    (interval.upper[h] - forecast.mean[h] - half_width).abs(),
+                    1.0e-7
+                );
+                assert_lt!(
+                    (forecast.mean[h] - interval.lower[h] - half_width).abs(),
+                    1.0e-7
+                );
+            }
+        }
+        assert_eq!(forecast.intervals[1].level, 0.95);
+
+        assert!(model.predict(4, &[95.0]).is_err());
+    }
+
+    #[test]
+    fn predict_arima_011_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 0, 1, 1).unwrap();
+        let forecast = model.predict(4, &[]).unwrap();
+
+        // psi_0 = 1 and psi_j = 1 + theta for j > 0
+        let theta = model.theta()[0];
+        for h in 0..4 {
+            let var = 1.0 + h as f64 * (1.0 + theta).powi(2);
+            let se = (model.sigma2() * var).sqrt();
+            assert_lt!((forecast.std_errors[h] - se).abs(), 1.0e-7);
+        }
+        assert!(forecast.intervals.is_empty());
+
+        // the variance of a seasonal random walk grows once per season
+        let x = [1.0, 5.0, 3.0, 2.0, 6.0, 4.0, 3.0, 7.0, 5.0];
+        let options = arima::estimate::FitOptions::new().with_seasonal(0, 1, 0, 3);
+        let model = arima::estimate::fit_with_options(&x, 0, 0, 0, &options).unwrap();
+        let forecast = model.predict(7, &[0.95]).unwrap();
+        let var_real = [1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0];
+        for (se, var) in forecast.std_errors.iter().zip(var_real) {
+            assert_lt!((se - (model.sigma2() * var).sqrt()).abs(), 1.0e-7);
+        }
+    }
+
+    #[test]
+    fn forecast_xreg_f64() {
+        let x = [1.2, 2.9, 1.1, 3.2, 0.8, 3.1, 1.0, 2.8];
+        let z = vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let options = arima::estimate::FitOptions::new().with_xreg(vec![z]);
+        let model = arima::estimate::fit_with_options(&x, 0, 0, 0, &options).unwrap();
+
+        // without ARMA terms, the forecast is the regression line
+        let c = model.intercept();
+        let beta = model.xreg_coef()[0];
+        assert_lt!((c - 1.025).abs(), 1.0e-3);
+        assert_lt!((beta - 1.975).abs(), 1.0e-3);
+
+        let forecast = model.forecast_xreg(&[vec![1.0, 0.0, 2.0]]).unwrap();
+        assert_lt!((forecast[0] - (c + beta)).abs(), 1.0e-7);
+        assert_lt!((forecast[1] - c).abs(), 1.0e-7);
+        assert_lt!((forecast[2] - (c + 2.0 * beta)).abs(), 1.0e-7);
+
+        let forecast = model.predict_xreg(&[vec![1.0, 0.0, 2.0]], &[0.9]).unwrap();
+        assert_lt!((forecast.mean[2] - (c + 2.0 * beta)).abs(), 1.0e-7);
+        assert_lt!(
+            (forecast.std_errors[2] - model.sigma2().sqrt()).abs(),
+            1.0e-7
+        );
+
+        // regressors are required to forecast
+        assert!(model.forecast(3).is_err());
+        assert!(model.forecast_xreg(&[]).is_err());
+    }
+
+    #[test]
+    fn std_errors_ar1_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x = arima::sim::arima_sim(
+            1000,
+            Some(&[0.5]),
+            None,
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        for method in [arima::estimate::Method::Css, arima::estimate::Method::CssMl] {
+            let options = arima::estimate::FitOptions::new().with_method(method);
+            let model = arima::estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+
+            // asymptotic standard error of an AR(1) coefficient: sqrt((1 - phi^2) / n)
+            let phi = model.phi()[0];
+            let se_real = ((1.0 - phi * phi) / 1000.0).sqrt();
+            let se = model.std_errors();
+            assert_eq!(se.len(), 2);
+            assert_lt!((se[1] - se_real).abs(), 0.1 * se_real);
+
+            // covariance matrix is symmetric
+            let cov = model.cov();
+            assert_lt!((cov[0][1] - cov[1][0]).abs(), 1.0e-10);
+
+            let z = model.z_stats();
+            assert_lt!((z[1] - phi / se[1]).abs(), 1.0e-10);
+            let p = model.p_values();
+            assert_lt!(p[1], 1.0e-10);
+            assert!(p[0] > 0.0 && p[0] <= 1.0);
+        }
+    }
+
+    #[test]
+    fn root_moduli_arima_201_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 2, 0, 1).unwrap();
+
+        // the product of the roots of 1 - phi_1 z - phi_2 z^2 is -1/phi_2
+        let ar = model.ar_root_moduli();
+        assert_eq!(ar.len(), 2);
+        assert_lt!(ar[0], ar[1] + 1.0e-10);
+        assert_lt!((ar[0] * ar[1] - 1.0 / model.phi()[1].abs()).abs(), 1.0e-7);
+
+        let ma = model.ma_root_moduli();
+        assert_eq!(ma.len(), 1);
+        assert_lt!((ma[0] - 1.0 / model.theta()[0].abs()).abs(), 1.0e-7);
+    }
+
+    #[test]
+    fn root_moduli_sarima_f64() {
+        let x = AR3;
+        let options = arima::estimate::FitOptions::new().with_seasonal(1, 0, 0, 4);
+        let model = arima::estimate::fit_with_options(&x, 1, 0, 0, &options).unwrap();
+
+        // (1 - phi z)(1 - Phi z^4) has one root of modulus 1/|phi| and four of 1/|Phi|^(1/4)
+        let moduli = model.ar_root_moduli();
+        assert_eq!(moduli.len(), 5);
+        let phi = model.phi()[0].abs();
+        let sphi = model.seasonal_phi()[0].abs();
+        let mut real = vec![1.0 / phi, 1.0 / sphi.powf(0.25)];
+        real.extend(vec![1.0 / sphi.powf(0.25); 3]);
+        real.sort_by(|a, b| a.total_cmp(b));
+        for (m, r) in moduli.iter().zip(&real) {
+            assert_lt!((m - r).abs(), 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn information_criteria_f64() {
+        let x = AR3;
+        let model = arima::estimate::fit(&x, 1, 0, 1).unwrap();
+
+        // three coefficients and the innovation variance
+        let k = 4.0;
+        let n = model.nobs() as f64;
+        let aic = -2.0 * model.loglik() + 2.0 * k;
+        assert_lt!((model.aic() - aic).abs(), 1.0e-10);
+        assert_lt!(
+            (model.aicc() - aic - 2.0 * k * (k + 1.0) / (n - k - 1.0)).abs(),
+            1.0e-10
+        );
+        assert_lt!(
+            (model.bic() - (-2.0 * model.loglik() + k * n.ln())).abs(),
+            1.0e-10
+        );
+    }
+}
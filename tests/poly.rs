@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod test_poly {
+    use more_asserts::assert_lt;
+
+    #[test]
+    fn roots_f64() {
+        // (z - 1)(z + 2)(z - 3) = 6 - 5z - 2z^2 + z^3
+        let mut roots: Vec<f64> = arima::poly::roots(&[6.0, -5.0, -2.0, 1.0])
+            .iter()
+            .map(|z| {
+                assert_lt!(z.im.abs(), 1.0e-10);
+                z.re
+            })
+            .collect();
+        roots.sort_by(|a, b| a.total_cmp(b));
+        for (a, b) in roots.iter().zip(&[-2.0, 1.0, 3.0]) {
+            assert_lt!((a - b).abs(), 1.0e-10);
+        }
+
+        // 1 - z + z^2 has the complex conjugate roots 0.5 -/+ 0.866i of modulus 1
+        let roots = arima::poly::roots(&[1.0, -1.0, 1.0]);
+        assert_eq!(roots.len(), 2);
+        for z in &roots {
+            assert_lt!((z.re - 0.5).abs(), 1.0e-10);
+            assert_lt!((z.im.abs() - 0.75_f64.sqrt()).abs(), 1.0e-10);
+        }
+        assert_lt!((roots[0].im + roots[1].im).abs(), 1.0e-10);
+
+        // trailing zeros do not add roots
+        assert_eq!(arima::poly::roots(&[1.0, 2.0, 0.0]).len(), 1);
+        assert!(arima::poly::roots(&[1.0]).is_empty());
+    }
+
+    #[test]
+    fn ar_ma_roots_f64() {
+        // 1 - 1.0z + 0.25z^2 = (1 - 0.5z)^2 has a double root at 2
+        let moduli: Vec<f64> = arima::poly::ar_roots(&[1.0, -0.25])
+            .iter()
+            .map(|z| z.norm())
+            .collect();
+        for m in moduli {
+            assert_lt!((m - 2.0).abs(), 1.0e-6);
+        }
+        assert!(arima::poly::is_stationary(&[1.0, -0.25]));
+        assert!(arima::poly::is_stationary(&[]));
+        assert!(!arima::poly::is_stationary(&[1.0]));
+        assert!(!arima::poly::is_stationary(&[0.5, 0.6]));
+
+        // 1 + 0.5z + 0.8z^2 has complex roots of modulus sqrt(1/0.8)
+        for z in arima::poly::ma_roots(&[0.5, 0.8]) {
+            assert_lt!((z.norm() - 1.25_f64.sqrt()).abs(), 1.0e-10);
+        }
+        assert!(arima::poly::is_invertible(&[0.5, 0.8]));
+        assert!(!arima::poly::is_invertible(&[0.5, 1.2]));
+    }
+
+    #[test]
+    fn cancelling_roots_sim() {
+        use rand::prelude::*;
+        use rand_distr::{Distribution, Normal};
+
+        // common factor (1 - 0.5z) in both polynomials
+        let pairs = arima::poly::cancelling_roots(&[1.2, -0.35], &[-0.2, -0.15], 1.0e-6);
+        assert_eq!(pairs.len(), 1);
+        assert_lt!((pairs[0].0.re - 2.0).abs(), 1.0e-6);
+        assert!(arima::poly::cancelling_roots(&[0.5], &[], 1.0).is_empty());
+
+        // an ARMA(1,1) model of white noise is over-parameterized
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let x: Vec<f64> = (0..500).map(|_| normal.sample(&mut rng)).collect();
+        let model = arima::estimate::fit(&x, 1, 0, 1).unwrap();
+        assert_eq!(model.cancelling_roots(0.2).len(), 1);
+    }
+}
@@ -45,4 +45,101 @@ mod test_sim {
 
         assert!(pacf - 0.9 < 0.05);
     }
+
+    #[test]
+    fn sim_sarima() {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        let x = arima::sim::sarima_sim(
+            1000,
+            None,
+            None,
+            0,
+            &arima::sim::Seasonal {
+                ar: Some(&[0.8]),
+                ma: None,
+                d: 0,
+                period: 4,
+            },
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert_eq!(x.len(), 1000);
+
+        // a pure seasonal AR(1) model only correlates at multiples of the period
+        let acf = arima::acf::acf(&x, Some(4), false).unwrap();
+        assert!(acf[1].abs() < 0.1);
+        assert!((acf[4] - 0.8).abs() < 0.1);
+
+        // the series has to be at least as long as the order of differencing
+        let seasonal = arima::sim::Seasonal {
+            d: 1,
+            period: 12,
+            ..Default::default()
+        };
+        let noise = |rng: &mut StdRng| normal.sample(rng);
+        assert!(arima::sim::sarima_sim(5, None, None, 0, &seasonal, &noise, &mut rng).is_err());
+        let x = arima::sim::sarima_sim(13, None, None, 1, &seasonal, &noise, &mut rng).unwrap();
+        assert_eq!(x.len(), 13);
+    }
+
+    #[test]
+    fn forecast_integrated() {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let ts = [1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+
+        // without noise, an ARIMA(1,1,0) forecast continues from the last level
+        let x = arima::sim::arima_forecast(&ts, 3, Some(&[0.5]), None, 1, &|_, _| 0.0, &mut rng)
+            .unwrap();
+        assert_eq!(x, [8.5, 8.75, 8.875]);
+
+        // a seasonal random walk repeats the last season
+        let seasonal = arima::sim::Seasonal {
+            ar: None,
+            ma: None,
+            d: 1,
+            period: 3,
+        };
+        let x =
+            arima::sim::sarima_forecast(&ts, 4, None, None, 0, &seasonal, &|_, _| 0.0, &mut rng)
+                .unwrap();
+        assert_eq!(x, [5.0, 7.0, 8.0, 5.0]);
+
+        assert!(arima::sim::arima_forecast(
+            &ts[..2],
+            3,
+            Some(&[0.5, 0.2]),
+            None,
+            1,
+            &|_, _| 0.0,
+            &mut rng
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn forecast_arma_residuals() {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let ts = [0.5, -0.3, 1.2, 0.8, -0.4, 0.1];
+        let (phi, theta) = (0.6, 0.4);
+
+        // without noise, the MA part of the first step uses the last in-sample residual
+        let e = arima::estimate::residuals(&ts, 0.0, Some(&[phi]), Some(&[theta])).unwrap();
+        let x = arima::sim::arima_forecast(
+            &ts,
+            2,
+            Some(&[phi]),
+            Some(&[theta]),
+            0,
+            &|_, _| 0.0,
+            &mut rng,
+        )
+        .unwrap();
+        let f1 = phi * ts[5] + theta * e[5];
+        assert!((x[0] - f1).abs() < 1.0e-12);
+        assert!((x[1] - phi * f1).abs() < 1.0e-12);
+    }
 }
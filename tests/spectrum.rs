@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod test_spectrum {
+    use more_asserts::assert_lt;
+    use rand::prelude::*;
+    use rand_distr::{Distribution, Normal};
+
+    const X: [f64; 21] = [
+        149.8228533548,
+        86.8388399871,
+        42.3116899484,
+        76.6796578536,
+        60.3665347774,
+        66.7733563129,
+        -5.1144504108,
+        14.0294086329,
+        76.2517878809,
+        121.2898170491,
+        74.65663878,
+        69.9331198692,
+        46.7476543397,
+        26.2225173663,
+        -32.0638217183,
+        2.8335240789,
+        31.5182582874,
+        76.4827451823,
+        36.6122657518,
+        -33.430444607,
+        12.5,
+    ];
+
+    fn sim_ar1(n: usize, phi: f64) -> Vec<f64> {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        arima::sim::arima_sim(
+            n,
+            Some(&[phi]),
+            None,
+            0,
+            &|mut rng| normal.sample(&mut rng),
+            &mut rng,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn periodogram_f64() {
+        // compare with the directly computed DFT for a length that is not a power of two
+        let n = X.len();
+        let mean = X.iter().sum::<f64>() / n as f64;
+        let pgram = arima::spectrum::periodogram(&X).unwrap();
+        assert_eq!(pgram.freq.len(), n / 2);
+        assert_eq!(pgram.df, Some(2.0));
+        for (k, (f, p)) in pgram.freq.iter().zip(&pgram.spec).enumerate() {
+            assert_lt!((f - (k + 1) as f64 / n as f64).abs(), 1.0e-12);
+            let (mut re, mut im) = (0.0, 0.0);
+            for (t, x) in X.iter().enumerate() {
+                let w = 2.0 * std::f64::consts::PI * f * t as f64;
+                re += (x - mean) * w.cos();
+                im -= (x - mean) * w.sin();
+            }
+            let p_real = (re * re + im * im) / n as f64;
+            assert_lt!((p - p_real).abs(), 1.0e-9 * p_real.max(1.0));
+        }
+
+        // Parseval: for odd n, the periodogram sums up to half of the sum of squares
+        let sum_sq: f64 = X.iter().map(|x| (x - mean).powi(2)).sum();
+        let sum_pgram: f64 = pgram.spec.iter().sum();
+        assert_lt!((2.0 * sum_pgram - sum_sq).abs(), 1.0e-9 * sum_sq);
+
+        assert!(arima::spectrum::periodogram(&[1.0]).is_err());
+    }
+
+    #[test]
+    fn periodogram_options_f64() {
+        let raw = arima::spectrum::periodogram(&X).unwrap();
+        let n = raw.spec.len();
+
+        // the Daniell kernel is a moving average of the raw periodogram away from the ends
+        let options = arima::spectrum::SpectrumOptions::new()
+            .with_kernel(arima::spectrum::Kernel::Daniell(1));
+        let smooth = arima::spectrum::periodogram_with_options(&X, &options).unwrap();
+        assert_eq!(smooth.spec.len(), n);
+        assert_lt!((smooth.df.unwrap() - 6.0).abs(), 1.0e-12);
+        for k in 1..n - 1 {
+            let avg = (raw.spec[k - 1] + raw.spec[k] + raw.spec[k + 1]) / 3.0;
+            assert_lt!((smooth.spec[k] - avg).abs(), 1.0e-9 * avg);
+        }
+
+        // two modified Daniell kernels are combined by convolution
+        let options = arima::spectrum::SpectrumOptions::new()
+            .with_kernel(arima::spectrum::Kernel::ModifiedDaniell(1))
+            .with_kernel(arima::spectrum::Kernel::ModifiedDaniell(1));
+        let smooth = arima::spectrum::periodogram_with_options(&X, &options).unwrap();
+        // weights 1/16, 4/16, 6/16, 4/16, 1/16
+        assert_lt!((smooth.df.unwrap() - 2.0 / (70.0 / 256.0)).abs(), 1.0e-12);
+        let k = 4;
+        let avg = (raw.spec[k - 2]
+            + 4.0 * raw.spec[k - 1]
+            + 6.0 * raw.spec[k]
+            + 4.0 * raw.spec[k + 1]
+            + raw.spec[k + 2])
+            / 16.0;
+        assert_lt!((smooth.spec[k] - avg).abs(), 1.0e-9 * avg);
+
+        // tapering and detrending keep the frequencies and reduce the degrees of freedom
+        let options = arima::spectrum::SpectrumOptions::new()
+            .with_taper(0.1)
+            .with_detrend(true);
+        let tapered = arima::spectrum::periodogram_with_options(&X, &options).unwrap();
+        assert_eq!(tapered.freq, raw.freq);
+        assert_lt!(tapered.df.unwrap(), 2.0);
+
+        // a linear trend does not change the detrended periodogram
+        let trend: Vec<f64> = X
+            .iter()
+            .enumerate()
+            .map(|(t, x)| x + 3.0 * t as f64)
+            .collect();
+        let detrended = arima::spectrum::periodogram_with_options(&trend, &options).unwrap();
+        for (a, b) in detrended.spec.iter().zip(&tapered.spec) {
+            assert_lt!((a - b).abs(), 1.0e-9 * b.max(1.0));
+        }
+
+        let options = arima::spectrum::SpectrumOptions::new().with_taper(0.6);
+        assert!(arima::spectrum::periodogram_with_options(&X, &options).is_err());
+        let options = arima::spectrum::SpectrumOptions::new()
+            .with_kernel(arima::spectrum::Kernel::Daniell(20));
+        assert!(arima::spectrum::periodogram_with_options(&X, &options).is_err());
+    }
+
+    #[test]
+    fn arma_spectrum_f64() {
+        // the density integrates to the variance of the process
+        let phi = [0.6, -0.2];
+        let theta = [0.3];
+        let sigma2 = 2.0;
+        let m = 20000;
+        let freq: Vec<f64> = (0..m).map(|k| (k as f64 + 0.5) / m as f64 - 0.5).collect();
+        let spec = arima::spectrum::arma_spectrum(&phi, &theta, sigma2, &freq);
+        let integral: f64 = spec.iter().sum::<f64>() / m as f64;
+        let gamma0 = sigma2 * arima::arma::theoretical_acf(&phi, &theta, 0, true).unwrap()[0];
+        assert_lt!((integral - gamma0).abs(), 1.0e-8);
+
+        // the spectrum of an MA(1) process is 1 + theta^2 + 2 theta cos(2 pi f)
+        let spec = arima::spectrum::arma_spectrum(&[], &[0.5], 1.0, &[0.0, 0.25, 0.5]);
+        assert_lt!((spec[0] - 2.25).abs(), 1.0e-12);
+        assert_lt!((spec[1] - 1.25).abs(), 1.0e-12);
+        assert_lt!((spec[2] - 0.25).abs(), 1.0e-12);
+    }
+
+    #[test]
+    fn spectrum_sim() {
+        let phi = 0.7;
+        let x = sim_ar1(4096, phi);
+
+        // the smoothed periodogram scatters around the theoretical density
+        let options = arima::spectrum::SpectrumOptions::new()
+            .with_taper(0.1)
+            .with_kernel(arima::spectrum::Kernel::ModifiedDaniell(10));
+        let smooth = arima::spectrum::periodogram_with_options(&x, &options).unwrap();
+        let real = arima::spectrum::arma_spectrum(&[phi], &[], 1.0, &smooth.freq);
+        let mean_log_ratio = smooth
+            .spec
+            .iter()
+            .zip(&real)
+            .map(|(s, r)| (s / r).ln())
+            .sum::<f64>()
+            / real.len() as f64;
+        assert_lt!(mean_log_ratio.abs(), 0.05);
+
+        // the AR spectrum selects a low order and is close to the theoretical density
+        let ar = arima::spectrum::ar_spectrum(&x, None, 101).unwrap();
+        assert_eq!(ar.df, None);
+        let real = arima::spectrum::arma_spectrum(&[phi], &[], 1.0, &ar.freq);
+        for (s, r) in ar.spec.iter().zip(&real) {
+            assert_lt!((s / r - 1.0).abs(), 0.15);
+        }
+
+        // and so is the density of a fitted model
+        let model = arima::estimate::fit(&x, 1, 0, 0).unwrap();
+        let fitted = model.spectral_density(&ar.freq);
+        for (s, r) in fitted.iter().zip(&real) {
+            assert_lt!((s / r - 1.0).abs(), 0.15);
+        }
+
+        assert!(arima::spectrum::ar_spectrum(&x, Some(2), 1).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod test_stats {
+    use more_asserts::assert_lt;
+
+    #[test]
+    fn normal_cdf_f64() {
+        let x = [
+            -5.0,
+            -1.959963984540054,
+            -1.0,
+            0.0,
+            0.5,
+            1.644853626951472,
+            3.0,
+        ];
+        let cdf_real = [
+            2.866515718791939e-07,
+            0.025,
+            0.15865525393145707,
+            0.5,
+            0.6914624612740131,
+            0.95,
+            0.9986501019683699,
+        ];
+        for (xi, real) in x.iter().zip(&cdf_real) {
+            assert_lt!(
+                (arima::stats::normal_cdf(*xi) - real).abs(),
+                1.0e-12 * real.max(1e-3)
+            );
+        }
+        assert!(arima::stats::normal_cdf(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn normal_ppf_f64() {
+        let p = [1.0e-10, 0.001, 0.025, 0.1, 0.5, 0.8, 0.95, 0.999];
+        let ppf_real = [
+            -6.361340902404056,
+            -3.090232306167813,
+            -1.959963984540054,
+            -1.2815515655446004,
+            0.0,
+            0.8416212335729143,
+            1.6448536269514722,
+            3.090232306167813,
+        ];
+        for (pi, real) in p.iter().zip(&ppf_real) {
+            assert_lt!(
+                (arima::stats::normal_ppf(*pi) - real).abs(),
+                1.0e-10 * real.abs().max(1.0)
+            );
+        }
+        assert_eq!(arima::stats::normal_ppf(0.0), f64::NEG_INFINITY);
+        assert_eq!(arima::stats::normal_ppf(1.0), f64::INFINITY);
+        assert!(arima::stats::normal_ppf(1.5).is_nan());
+    }
+
+    #[test]
+    fn chi2_sf_f64() {
+        // 95% quantiles
+        let quantiles = [
+            (1.0, 3.841458820694124),
+            (5.0, 11.070497693516351),
+            (10.0, 18.307038053275146),
+        ];
+        for (df, q) in quantiles {
+            assert_lt!((arima::stats::chi2_sf(q, df) - 0.05).abs(), 1.0e-12);
+        }
+
+        // closed forms for two and four degrees of freedom
+        for x in [0.1_f64, 1.0, 5.0, 30.0] {
+            let sf2 = (-0.5 * x).exp();
+            let sf4 = (-0.5 * x).exp() * (1.0 + 0.5 * x);
+            assert_lt!(
+                (arima::stats::chi2_sf(x, 2.0) - sf2).abs(),
+                1.0e-12 * sf2.max(1e-3)
+            );
+            assert_lt!(
+                (arima::stats::chi2_sf(x, 4.0) - sf4).abs(),
+                1.0e-12 * sf4.max(1e-3)
+            );
+        }
+
+        assert_eq!(arima::stats::chi2_sf(0.0, 3.0), 1.0);
+        assert!(arima::stats::chi2_sf(1.0, 0.0).is_nan());
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod test_unitroot {
+    use arima::unitroot::{self, LagSelection, Regression, UnitRootTest};
+    use more_asserts::assert_lt;
+    use rand::prelude::*;
+    use rand_distr::{Distribution, Normal};
+
+    fn noise(n: usize) -> Vec<f64> {
+        let mut rng: StdRng = SeedableRng::from_seed([100; 32]);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        (0..n).map(|_| normal.sample(&mut rng)).collect()
+    }
+
+    fn cumsum(x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .scan(0.0, |acc, v| {
+                *acc += v;
+                Some(*acc)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adf_f64() {
+        let e = noise(300);
+        let rw = cumsum(&e);
+
+        for regression in [
+            Regression::NoConstant,
+            Regression::Constant,
+            Regression::ConstantTrend,
+        ] {
+            // white noise has no unit root
+            let result = unitroot::adf(&e, regression, LagSelection::Aic).unwrap();
+            assert_lt!(result.p_value, 0.01);
+            assert_lt!(result.statistic, result.critical_values[0].1);
+
+            // a random walk has
+            let result = unitroot::adf(&rw, regression, LagSelection::Bic).unwrap();
+            assert_lt!(0.1, result.p_value);
+            assert_lt!(result.critical_values[2].1, result.statistic);
+
+            // critical values increase with the significance level
+            let cv = &result.critical_values;
+            assert_eq!(cv.len(), 3);
+            assert_lt!(cv[0].1, cv[1].1);
+            assert_lt!(cv[1].1, cv[2].1);
+        }
+
+        // asymptotic 5% critical value with a constant
+        let result = unitroot::adf(&rw, Regression::Constant, LagSelection::Fixed(2)).unwrap();
+        assert_eq!(result.lags, 2);
+        assert_eq!(result.nobs, 297);
+        assert_lt!((result.critical_values[1].1 + 2.8715).abs(), 1.0e-3);
+    }
+
+    #[test]
+    fn kpss_f64() {
+        let x = [1.0, 3.0, 2.0, 5.0, 4.0, 6.0];
+
+        // without lags, the statistic is sum(S_t^2) / (n^2 * mean(e_t^2))
+        let e: Vec<f64> = x.iter().map(|v| v - 3.5).collect();
+        let s2 = e.iter().map(|v| v * v).sum::<f64>() / 6.0;
+        let eta: f64 = cumsum(&e).iter().map(|s| s * s).sum();
+        let result = unitroot::kpss(&x, Regression::Constant, Some(0)).unwrap();
+        assert_lt!((result.statistic - eta / (36.0 * s2)).abs(), 1.0e-10);
+
+        let e = noise(300);
+        let result = unitroot::kpss(&e, Regression::Constant, None).unwrap();
+        assert_eq!(result.lags, 5);
+        assert_eq!(result.p_value, 0.1);
+        let result = unitroot::kpss(&cumsum(&e), Regression::Constant, None).unwrap();
+        assert_eq!(result.p_value, 0.01);
+
+        // a linear trend is trend stationary, but not level stationary
+        let x: Vec<f64> = e
+            .iter()
+            .enumerate()
+            .map(|(t, v)| 0.1 * t as f64 + v)
+            .collect();
+        let result = unitroot::kpss(&x, Regression::ConstantTrend, None).unwrap();
+        assert_lt!(0.05, result.p_value);
+        let result = unitroot::kpss(&x, Regression::Constant, None).unwrap();
+        assert_lt!(result.p_value, 0.05);
+
+        assert!(unitroot::kpss(&x, Regression::NoConstant, None).is_err());
+    }
+
+    #[test]
+    fn ndiffs_f64() {
+        let e = noise(300);
+        let rw = cumsum(&e);
+        let rw2 = cumsum(&rw);
+
+        for test in [UnitRootTest::Kpss, UnitRootTest::Adf] {
+            assert_eq!(unitroot::ndiffs(&e, test, 0.05, 2).unwrap(), 0);
+            assert_eq!(unitroot::ndiffs(&rw, test, 0.05, 2).unwrap(), 1);
+            assert_eq!(unitroot::ndiffs(&rw2, test, 0.05, 2).unwrap(), 2);
+            assert_eq!(unitroot::ndiffs(&rw2, test, 0.05, 1).unwrap(), 1);
+        }
+    }
+}
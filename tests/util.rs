@@ -25,7 +25,7 @@ mod test_util {
 
     #[test]
     fn diff_1_f64() {
-        let x = [
+        let x: [f64; 10] = [
             4.1341055, 4.5212322, -9.1234667, -1.3249472, -8.9102578, -7.5955399, -1.8054393,
             8.6400979, 0.7207072, 6.6751565,
         ];
@@ -45,13 +45,13 @@ mod test_util {
         assert_eq!(x_diff.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_diff[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_diff[i] - y[i]).abs(), 1.0e-7);
         }
     }
 
     #[test]
     fn diff_2_f64() {
-        let x = [
+        let x: [f64; 10] = [
             4.1341055, 4.5212322, -9.1234667, -1.3249472, -8.9102578, -7.5955399, -1.8054393,
             8.6400979, 0.7207072, 6.6751565,
         ];
@@ -70,13 +70,13 @@ mod test_util {
         assert_eq!(x_diff.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_diff[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_diff[i] - y[i]).abs(), 1.0e-7);
         }
     }
 
     #[test]
     fn diff_log_f64() {
-        let x = [
+        let x: [f64; 10] = [
             9.9902684, 4.3772393, 1.8550282, 9.7252195, 2.8445105, 0.2348111, 7.6587723, 8.9285881,
             7.6012410, 3.6073980,
         ];
@@ -89,7 +89,7 @@ mod test_util {
         assert_eq!(x_diff.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_diff[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_diff[i] - y[i]).abs(), 1.0e-7);
         }
     }
 
@@ -105,7 +105,7 @@ mod test_util {
 
     #[test]
     fn cumsum_f64() {
-        let x = [
+        let x: [f64; 10] = [
             4.1341055, 4.5212322, -9.1234667, -1.3249472, -8.9102578, -7.5955399, -1.8054393,
             8.6400979, 0.7207072, 6.6751565,
         ];
@@ -126,7 +126,7 @@ mod test_util {
         assert_eq!(x_cumsum.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_cumsum[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_cumsum[i] - y[i]).abs(), 1.0e-7);
         }
     }
 
@@ -157,7 +157,7 @@ mod test_util {
 
     #[test]
     fn diffinv_1_f64() {
-        let x = [
+        let x: [f64; 10] = [
             4.1341055, 4.5212322, -9.1234667, -1.3249472, -8.9102578, -7.5955399, -1.8054393,
             8.6400979, 0.7207072, 6.6751565,
         ];
@@ -179,20 +179,20 @@ mod test_util {
         assert_eq!(x_diffinv.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_diffinv[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_diffinv[i] - y[i]).abs(), 1.0e-7);
         }
 
         // check backwards
         let z = arima::util::diff(&x_diffinv, 1);
 
         for i in 0..z.len() {
-            assert_lt!((z[i] - x[i] as f64).abs(), 1.0e-7);
+            assert_lt!((z[i] - x[i]).abs(), 1.0e-7);
         }
     }
 
     #[test]
     fn diffinv_2_f64() {
-        let x = [
+        let x: [f64; 10] = [
             4.1341055, 4.5212322, -9.1234667, -1.3249472, -8.9102578, -7.5955399, -1.8054393,
             8.6400979, 0.7207072, 6.6751565,
         ];
@@ -215,7 +215,78 @@ mod test_util {
         assert_eq!(x_diffinv.len(), y.len());
 
         for i in 0..y.len() {
-            assert_lt!((x_diffinv[i] - y[i] as f64).abs(), 1.0e-7);
+            assert_lt!((x_diffinv[i] - y[i]).abs(), 1.0e-7);
         }
     }
+
+    #[test]
+    fn diff_seasonal_i32() {
+        let x = [-4, -9, 20, 23, -18, 6, 1, 5];
+        let y = [27, -9, -14, -22, 23];
+        assert_eq!(arima::util::diff_seasonal(&x, 1, 3), y);
+
+        let z = [-49, 32];
+        assert_eq!(arima::util::diff_seasonal(&x, 2, 3), z);
+    }
+
+    #[test]
+    fn diffinv_seasonal_2_i32() {
+        let x = [-49, 14];
+        let y = [0, 0, 0, 0, 0, 0, -49, 14];
+
+        let x_diffinv = arima::util::diffinv_seasonal(&x, 2, 3);
+        assert_eq!(x_diffinv, y);
+
+        // check backwards
+        let z = arima::util::diff_seasonal(&x_diffinv, 2, 3);
+        assert_eq!(z, x);
+    }
+
+    #[test]
+    fn integrate_f64() {
+        let x = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0, 5.0];
+
+        // integrating the differences of the tail of a series restores the tail
+        for (d, sd, period) in [(0, 0, 0), (1, 0, 0), (2, 0, 0), (0, 1, 3), (1, 1, 2)] {
+            let w = arima::util::diff(&x, d);
+            let w = arima::util::diff_seasonal(&w, sd, period);
+            let n_tail = 3;
+            let y = arima::util::integrate(
+                &x[..x.len() - n_tail],
+                &w[w.len() - n_tail..],
+                d,
+                sd,
+                period,
+            )
+            .unwrap();
+            assert_eq!(y, x[x.len() - n_tail..]);
+        }
+
+        assert!(arima::util::integrate(&[1.0], &[1.0], 2, 0, 0).is_err());
+    }
+
+    #[test]
+    fn expand_seasonal_f64() {
+        let phi = arima::util::expand_seasonal_ar(&[0.4_f64, 0.2], &[0.5, -0.3], 3);
+        let phi_real = [0.4, 0.2, 0.5, -0.2, -0.1, -0.3, 0.12, 0.06];
+
+        assert_eq!(phi.len(), phi_real.len());
+        for i in 0..phi.len() {
+            assert_lt!((phi[i] - phi_real[i]).abs(), 1.0e-12);
+        }
+
+        let theta = arima::util::expand_seasonal_ma(&[0.4_f64], &[0.5], 2);
+        let theta_real = [0.4, 0.5, 0.2];
+
+        assert_eq!(theta.len(), theta_real.len());
+        for i in 0..theta.len() {
+            assert_lt!((theta[i] - theta_real[i]).abs(), 1.0e-12);
+        }
+
+        // without seasonal part, the polynomials are unchanged
+        assert_eq!(
+            arima::util::expand_seasonal_ar(&[0.4, 0.2], &[], 12),
+            [0.4, 0.2]
+        );
+    }
 }